@@ -0,0 +1,89 @@
+use std::{env, fs, path::PathBuf, str::FromStr};
+
+/// 编辑器配置，启动时从配置文件中读取
+/// 配置文件位于 $XDG_CONFIG_HOME/ye/config（未设置时为 ~/.config/ye/config），
+/// 每行一项 `键 = 值`，以 # 开头的行是注释，缺省或无法解析的配置项使用默认值
+pub struct Config {
+    // 保存时是否把文件的上一个版本备份为 `文件名~`
+    pub backup: bool,
+    // 停止输入多少秒后自动保存，0 表示不自动保存
+    pub autosave_idle: u64,
+    // 编辑后光标离开所编辑的行时是否自动保存
+    pub autosave_on_leave: bool,
+    // 制表符的显示宽度
+    pub tab_width: usize,
+    // 按 Tab 键缩进时是否插入空格（软制表符）而不是制表符
+    pub soft_tabs: bool,
+    // 重排段落和自动折行的折行宽度
+    pub wrap_width: usize,
+    // 是否在输入时自动折行
+    pub auto_fill: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            backup: false,
+            autosave_idle: 0,
+            autosave_on_leave: false,
+            tab_width: 4,
+            soft_tabs: false,
+            wrap_width: 80,
+            auto_fill: false,
+        }
+    }
+}
+
+impl Config {
+    /// 读取配置文件，配置文件不存在时使用默认配置
+    pub fn load() -> Self {
+        let mut config = Self::default();
+
+        let Some(contents) = Self::path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return config;
+        };
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                config.set(key.trim(), value.trim());
+            }
+        }
+
+        config
+    }
+
+    /// 配置文件的路径
+    fn path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_home.join("ye").join("config"))
+    }
+
+    /// 设置一个配置项
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "backup" => Self::parse(value, &mut self.backup),
+            "autosave_idle" => Self::parse(value, &mut self.autosave_idle),
+            "autosave_on_leave" => Self::parse(value, &mut self.autosave_on_leave),
+            "tab_width" => Self::parse(value, &mut self.tab_width),
+            "soft_tabs" => Self::parse(value, &mut self.soft_tabs),
+            "wrap_width" => Self::parse(value, &mut self.wrap_width),
+            "auto_fill" => Self::parse(value, &mut self.auto_fill),
+            _ => {}
+        }
+    }
+
+    /// 解析配置项的值，无法解析时保持原值
+    fn parse<T: FromStr>(value: &str, field: &mut T) {
+        if let Ok(parsed) = value.parse() {
+            *field = parsed;
+        }
+    }
+}
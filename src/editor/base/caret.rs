@@ -0,0 +1,90 @@
+use crate::prelude::DocumentCoordinate;
+
+/// 光标及其选区：锚点是选择文本时固定不动的一端，另一端是光标；没有选区时为 None
+#[derive(Clone, Default)]
+pub struct Caret {
+    pub position: DocumentCoordinate,
+    pub anchor: Option<DocumentCoordinate>,
+}
+
+impl Caret {
+    /// 光标和锚点所围成的范围 [start, end)，没有选区时为空范围
+    pub fn range(&self) -> (DocumentCoordinate, DocumentCoordinate) {
+        let anchor = self.anchor.as_ref().unwrap_or(&self.position);
+
+        if *anchor < self.position {
+            (anchor.clone(), self.position.clone())
+        } else {
+            (self.position.clone(), anchor.clone())
+        }
+    }
+
+    /// 文本 [start, end) 被替换为以 text_end 结尾的文本后，调整光标和锚点，使其仍指向原来的文本
+    pub fn shift(
+        &mut self,
+        start: &DocumentCoordinate,
+        end: &DocumentCoordinate,
+        text_end: &DocumentCoordinate,
+    ) {
+        Self::shift_position(&mut self.position, start, end, text_end);
+        if let Some(anchor) = self.anchor.as_mut() {
+            Self::shift_position(anchor, start, end, text_end);
+        }
+    }
+
+    fn shift_position(
+        position: &mut DocumentCoordinate,
+        start: &DocumentCoordinate,
+        end: &DocumentCoordinate,
+        text_end: &DocumentCoordinate,
+    ) {
+        if *position <= *start {
+            return;
+        }
+
+        // 位于被替换的文本中，移动到新文本的末尾
+        if *position < *end {
+            *position = text_end.clone();
+            return;
+        }
+
+        if position.line_idx == end.line_idx {
+            position.cell_idx = text_end
+                .cell_idx
+                .saturating_add(position.cell_idx.saturating_sub(end.cell_idx));
+        }
+        position.line_idx = position
+            .line_idx
+            .saturating_sub(end.line_idx)
+            .saturating_add(text_end.line_idx);
+    }
+
+    /// 合并重叠的光标：选区有交集或位于同一位置的光标合并为一个，选区取并集
+    /// 每个光标附带一个标记（例如是否为主光标），合并后的光标只要有一个被标记就保留标记
+    pub fn merge(mut carets: Vec<(Caret, bool)>) -> Vec<(Caret, bool)> {
+        carets.sort_by_key(|(caret, _)| caret.range().0);
+
+        let mut merged: Vec<(Caret, bool)> = Vec::with_capacity(carets.len());
+        for (caret, flag) in carets {
+            let (start, end) = caret.range();
+
+            if let Some((last, last_flag)) = merged.last_mut() {
+                let (last_start, last_end) = last.range();
+
+                if start < last_end || start == last_start {
+                    let end = end.max(last_end);
+                    *last = Caret {
+                        anchor: (last_start != end).then(|| last_start.clone()),
+                        position: end,
+                    };
+                    *last_flag |= flag;
+                    continue;
+                }
+            }
+
+            merged.push((caret, flag));
+        }
+
+        merged
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    fmt::{Display, Formatter, Result},
+    mem,
+};
+
+/// 大小写和标识符命名风格
+#[derive(Clone, Copy)]
+pub enum Case {
+    Upper,
+    Lower,
+    Title,
+    Snake,
+    Camel,
+    Pascal,
+    Kebab,
+    ScreamingSnake,
+}
+
+impl Case {
+    /// 按键对应的风格：u 大写，l 小写，t 首字母大写，s snake_case，c camelCase，
+    /// p PascalCase，k kebab-case，S SCREAMING_SNAKE
+    pub fn from_key(key: char) -> Option<Self> {
+        match key {
+            'u' => Some(Self::Upper),
+            'l' => Some(Self::Lower),
+            't' => Some(Self::Title),
+            's' => Some(Self::Snake),
+            'c' => Some(Self::Camel),
+            'p' => Some(Self::Pascal),
+            'k' => Some(Self::Kebab),
+            'S' => Some(Self::ScreamingSnake),
+            _ => None,
+        }
+    }
+
+    /// 转换一串字素
+    /// 大小写转换作用于所有字素；命名风格只转换由字母、数字、`_`、`-` 组成的标识符，其他字素保持原样
+    pub fn convert(self, graphemes: &[&str]) -> String {
+        match self {
+            Self::Upper => graphemes
+                .iter()
+                .map(|grapheme| grapheme.to_uppercase())
+                .collect(),
+            Self::Lower => graphemes
+                .iter()
+                .map(|grapheme| grapheme.to_lowercase())
+                .collect(),
+            Self::Title => Self::convert_title(graphemes),
+            _ => Self::convert_identifiers(self, graphemes),
+        }
+    }
+
+    /// 每个单词首字母大写，其余字母小写
+    fn convert_title(graphemes: &[&str]) -> String {
+        let mut result = String::new();
+        let mut in_word = false;
+
+        for grapheme in graphemes {
+            let is_alphanumeric = Self::is_alphanumeric(grapheme);
+            if is_alphanumeric && !in_word {
+                result.push_str(&grapheme.to_uppercase());
+            } else {
+                result.push_str(&grapheme.to_lowercase());
+            }
+            in_word = is_alphanumeric;
+        }
+
+        result
+    }
+
+    /// 逐个转换标识符，标识符开头和结尾的 `_`、`-` 保持原样
+    fn convert_identifiers(self, graphemes: &[&str]) -> String {
+        let mut result = String::new();
+        let mut idx = 0;
+
+        while idx < graphemes.len() {
+            let end = graphemes[idx..]
+                .iter()
+                .position(|grapheme| !Self::is_identifier(grapheme))
+                .map_or(graphemes.len(), |len| idx.saturating_add(len));
+
+            if end == idx {
+                result.push_str(graphemes[idx]);
+                idx = idx.saturating_add(1);
+                continue;
+            }
+
+            let identifier = &graphemes[idx..end];
+            let core_start = identifier
+                .iter()
+                .position(|grapheme| !Self::is_separator(grapheme))
+                .unwrap_or(identifier.len());
+            let core_end = identifier
+                .iter()
+                .rposition(|grapheme| !Self::is_separator(grapheme))
+                .map_or(core_start, |pos| pos.saturating_add(1));
+
+            result.extend(identifier[..core_start].iter().copied());
+            result.push_str(&self.join(&Self::split_words(&identifier[core_start..core_end])));
+            result.extend(identifier[core_end..].iter().copied());
+            idx = end;
+        }
+
+        result
+    }
+
+    /// 按命名风格拼接单词
+    fn join(self, words: &[String]) -> String {
+        let words = words.iter().enumerate().map(|(idx, word)| match self {
+            Self::Snake | Self::Kebab => word.to_lowercase(),
+            Self::ScreamingSnake => word.to_uppercase(),
+            Self::Camel if idx == 0 => word.to_lowercase(),
+            _ => Self::capitalize(word),
+        });
+
+        match self {
+            Self::Snake | Self::ScreamingSnake => words.collect::<Vec<_>>().join("_"),
+            Self::Kebab => words.collect::<Vec<_>>().join("-"),
+            _ => words.collect(),
+        }
+    }
+
+    /// 把标识符拆分为单词：以 `_`、`-` 分隔，小写（或数字）后紧跟大写时分词，
+    /// 连续大写后紧跟小写字母时在最后一个大写前分词（如 HTTPServer 拆为 HTTP、Server，HTTP2 不拆分）
+    fn split_words(graphemes: &[&str]) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+
+        for (idx, grapheme) in graphemes.iter().enumerate() {
+            if Self::is_separator(grapheme) {
+                if !word.is_empty() {
+                    words.push(mem::take(&mut word));
+                }
+                continue;
+            }
+
+            if !word.is_empty() && Self::is_upper(grapheme) {
+                let prev = graphemes[idx.saturating_sub(1)];
+                let next = graphemes.get(idx.saturating_add(1)).copied();
+                if Self::is_lower(prev)
+                    || (Self::is_upper(prev) && next.is_some_and(Self::is_lower_letter))
+                {
+                    words.push(mem::take(&mut word));
+                }
+            }
+            word.push_str(grapheme);
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+
+        words
+    }
+
+    /// 首字母大写，其余字母小写
+    fn capitalize(word: &str) -> String {
+        let mut chars = word.chars();
+        chars.next().map_or(String::new(), |first| {
+            first
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect()
+        })
+    }
+
+    fn is_upper(grapheme: &str) -> bool {
+        grapheme.chars().next().is_some_and(char::is_uppercase)
+    }
+
+    /// 小写字母或数字
+    fn is_lower(grapheme: &str) -> bool {
+        grapheme
+            .chars()
+            .next()
+            .is_some_and(|char| char.is_lowercase() || char.is_numeric())
+    }
+
+    fn is_lower_letter(grapheme: &str) -> bool {
+        grapheme.chars().next().is_some_and(char::is_lowercase)
+    }
+
+    fn is_alphanumeric(grapheme: &str) -> bool {
+        grapheme.chars().next().is_some_and(char::is_alphanumeric)
+    }
+
+    fn is_separator(grapheme: &str) -> bool {
+        matches!(grapheme, "_" | "-")
+    }
+
+    fn is_identifier(grapheme: &str) -> bool {
+        Self::is_alphanumeric(grapheme) || Self::is_separator(grapheme)
+    }
+}
+
+impl Display for Case {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Upper => write!(formatter, "大写"),
+            Self::Lower => write!(formatter, "小写"),
+            Self::Title => write!(formatter, "首字母大写"),
+            Self::Snake => write!(formatter, "snake_case"),
+            Self::Camel => write!(formatter, "camelCase"),
+            Self::Pascal => write!(formatter, "PascalCase"),
+            Self::Kebab => write!(formatter, "kebab-case"),
+            Self::ScreamingSnake => write!(formatter, "SCREAMING_SNAKE"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use super::*;
+
+    fn split(text: &str) -> Vec<String> {
+        Case::split_words(&text.graphemes(true).collect::<Vec<_>>())
+    }
+
+    fn convert(case: Case, text: &str) -> String {
+        case.convert(&text.graphemes(true).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn split_words_on_case_changes() {
+        assert_eq!(split("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(split("parseHTTPResponse"), ["parse", "HTTP", "Response"]);
+        assert_eq!(
+            split("snake_case-and-kebab"),
+            ["snake", "case", "and", "kebab"]
+        );
+        assert_eq!(split("ALLCAPS"), ["ALLCAPS"]);
+    }
+
+    #[test]
+    fn split_words_with_digits() {
+        assert_eq!(split("version2Beta"), ["version2", "Beta"]);
+        assert_eq!(split("HTTP2Server"), ["HTTP2", "Server"]);
+        assert_eq!(split("utf8"), ["utf8"]);
+        assert_eq!(split("x_1_y"), ["x", "1", "y"]);
+    }
+
+    #[test]
+    fn split_words_with_non_ascii_letters() {
+        assert_eq!(split("straßeName"), ["straße", "Name"]);
+        assert_eq!(split("ÉcoleNormale"), ["École", "Normale"]);
+        assert_eq!(split("приветМир"), ["привет", "Мир"]);
+    }
+
+    #[test]
+    fn convert_identifiers_keeps_outer_separators() {
+        assert_eq!(convert(Case::Camel, "_leading_name"), "_leadingName");
+        assert_eq!(convert(Case::Snake, "trailingName-"), "trailing_name-");
+        assert_eq!(convert(Case::Pascal, "__both__"), "__Both__");
+        assert_eq!(convert(Case::Kebab, "--"), "--");
+    }
+
+    #[test]
+    fn convert_identifiers_in_text() {
+        assert_eq!(
+            convert(Case::Snake, "let HTTPServer = myValue2;"),
+            "let http_server = my_value2;"
+        );
+        assert_eq!(convert(Case::ScreamingSnake, "maxSize"), "MAX_SIZE");
+        assert_eq!(convert(Case::Kebab, "ÉcoleNormale"), "école-normale");
+        assert_eq!(convert(Case::Camel, "user_id (中文)"), "userId (中文)");
+    }
+}
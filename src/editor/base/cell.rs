@@ -1,63 +1,73 @@
+use std::borrow::Cow;
+
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+// 空格的可见符号
+const SPACE_SYMBOL: &str = "␣";
+
 // Cell 细胞，我称为图元，在视觉上是一个字符，但其宽度是不固定的。在unicode-segmentation 中称为 grapheme 字素。
 #[derive(Clone)]
 pub struct Cell {
-    // 图元的内容，终端将其渲染为视觉上的一个字符。
+    // 图元的内容，即文件中真实的字素，保存文档时原样写回。
     content: String,
     // 图元的渲染时宽度，用于终端渲染时确定宽度。
     cell_width: usize,
 }
 
 impl Cell {
-    pub fn to_string(&self) -> &String {
-        &self.content
-    }
+    /// 默认的制表符宽度
+    pub const DEFAULT_TAB_WIDTH: usize = 4;
 
     pub fn cell_width(&self) -> usize {
         self.cell_width
     }
 
-    /// 保存文档时，将图元转为对应的字符串
+    /// 图元对应的真实字素
     pub fn to_grapheme(&self) -> &str {
+        &self.content
+    }
+
+    /// 渲染图元时，终端上显示的内容
+    /// 制表符按宽度展开成空白；开启空白可见时，空格和制表符显示为“␣”
+    pub fn to_display(&self, show_whitespace: bool) -> Cow<'_, str> {
         match self.content.as_str() {
-            "␣" => " ",
-            "␣␣␣␣" => "\t",
-            _ => &self.content,
+            " " if show_whitespace => Cow::Borrowed(SPACE_SYMBOL),
+            "\t" if show_whitespace => Cow::Owned(SPACE_SYMBOL.repeat(self.cell_width)),
+            "\t" => Cow::Owned(" ".repeat(self.cell_width)),
+            _ => Cow::Borrowed(&self.content),
         }
     }
 
     pub fn char_to_cell(char: char) -> Cell {
-        Self::from_grapheme(&char.to_string())
+        Self::from_grapheme(&char.to_string(), Self::DEFAULT_TAB_WIDTH)
     }
 
-    // 将字符串转为图元向量, 还是有一些表情不支持。
-    pub fn str_to_cells(str: &str) -> Vec<Cell> {
-        let mut result = Vec::new();
+    // 将字符串转为图元向量，制表符展开到下一个制表位（tab_width 的整数倍列），字符串从第 0 列开始。
+    // 还是有一些表情不支持。
+    pub fn str_to_cells(str: &str, tab_width: usize) -> Vec<Cell> {
+        let tab_width = tab_width.max(1);
+        let mut col: usize = 0;
 
-        str.graphemes(true).for_each(|grapheme| {
-            // 对每一个 grapheme，都要替换成我们的 Cell 实例
-            result.push(Self::from_grapheme(grapheme));
-        });
-
-        return result;
+        str.graphemes(true)
+            .map(|grapheme| {
+                // 对每一个 grapheme，都要替换成我们的 Cell 实例
+                let cell = Self::from_grapheme(grapheme, tab_width - col % tab_width);
+                col = col.saturating_add(cell.cell_width);
+                cell
+            })
+            .collect()
     }
 
-    fn from_grapheme(grapheme: &str) -> Cell {
-        match grapheme {
-            " " => Cell {
-                content: "␣".to_string(),
-                cell_width: 1,
-            },
-            "\t" => Cell {
-                content: "␣␣␣␣".to_string(),
-                cell_width: 4,
-            },
-            _ => Cell {
-                content: grapheme.to_string(),
-                cell_width: grapheme.width(),
-            },
+    fn from_grapheme(grapheme: &str, tab_width: usize) -> Cell {
+        let cell_width = match grapheme {
+            "\t" => tab_width,
+            _ => grapheme.width(),
+        };
+
+        Cell {
+            content: grapheme.to_string(),
+            cell_width,
         }
     }
 }
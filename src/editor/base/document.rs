@@ -0,0 +1,349 @@
+use ropey::Rope;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    editor::{Cell, Line},
+    file::{Encoding, FileFormat, LineEnding},
+    prelude::{DocumentCoordinate, LineIdx},
+};
+
+// 查找配对括号时最多扫描的字符数，避免在大文件中逐字扫描
+const MAX_BRACKET_SCAN: usize = 100_000;
+
+/// 文档：以绳索（rope）结构存储文件的完整文本
+/// 绳索自带行索引，插入和删除的开销与文档大小无关；图行 Line 只在需要时（如绘制可视区域）才会被构建
+/// 文本按原样存储，换行符不做任何转换，因此保存时能够逐字节还原文件的换行风格
+/// 文件按原编码解码后再存入文档，保存时再编码回原编码
+pub struct Document {
+    rope: Rope,
+    // 文档保存时使用的字符编码
+    encoding: Encoding,
+    // 文档保存时是否写入 BOM
+    bom: bool,
+    // 文档的换行风格，新行使用此换行符
+    line_ending: LineEnding,
+    // 文档是否混用了多种换行符
+    mixed_line_endings: bool,
+    // 文档版本，文本每改动一次加一
+    version: usize,
+    // 制表符的显示宽度
+    tab_width: usize,
+}
+
+impl Document {
+    pub fn from(text: &str) -> Self {
+        let mut document = Self {
+            rope: Rope::from_str(text),
+            encoding: Encoding::default(),
+            bom: false,
+            line_ending: LineEnding::default(),
+            mixed_line_endings: false,
+            version: 0,
+            tab_width: Cell::DEFAULT_TAB_WIDTH,
+        };
+        document.detect_line_endings();
+
+        document
+    }
+
+    /// 从文件的原始字节构建文档，自动检测字符编码
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let (encoding, bom, text) = Encoding::decode(bytes);
+
+        let mut document = Self::from(&text);
+        document.encoding = encoding;
+        document.bom = bom;
+
+        document
+    }
+
+    /// 将文档按保存编码转为字节，文档中有该编码无法表示的字符时返回 None
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        self.encoding.encode(&self.rope.to_string(), self.bom)
+    }
+
+    /// 文档的完整文本
+    pub fn to_text(&self) -> String {
+        self.rope.to_string()
+    }
+
+    /// 文档版本，用于判断文档在两个时刻之间是否被改动过
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// 制表符的显示宽度
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    /// 修改制表符的显示宽度，至少为 1
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.tab_width = tab_width.max(1);
+    }
+
+    /// 修改文档保存时使用的字符编码
+    pub fn set_encoding(&mut self, encoding: Encoding, bom: bool) {
+        self.encoding = encoding;
+        self.bom = bom;
+        self.version = self.version.wrapping_add(1);
+    }
+
+    /// 文档的换行风格
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// 文档当前的文件格式
+    pub fn format(&self) -> FileFormat {
+        FileFormat {
+            encoding: self.encoding,
+            bom: self.bom,
+            line_ending: self.line_ending,
+            mixed_line_endings: self.mixed_line_endings,
+            final_newline: self.has_final_newline(),
+        }
+    }
+
+    /// 文档末尾是否有换行符，空文档视为有
+    pub fn has_final_newline(&self) -> bool {
+        let len_chars = self.rope.len_chars();
+        len_chars == 0 || matches!(self.rope.char(len_chars.saturating_sub(1)), '\n' | '\r')
+    }
+
+    /// 重新检测文档的换行风格
+    pub fn detect_line_endings(&mut self) {
+        let mut counts = [0; 3];
+        for chunk in self.rope.chunks() {
+            LineEnding::count(chunk, &mut counts);
+        }
+
+        (self.line_ending, self.mixed_line_endings) = LineEnding::dominant(&counts);
+    }
+
+    /// 指定行末尾的换行符，最后一行没有换行符
+    pub fn line_ending_of(&self, line_idx: LineIdx) -> Option<LineEnding> {
+        let line = self.rope.get_line(line_idx)?;
+        let len_chars = line.len_chars();
+        let last = len_chars.checked_sub(1).map(|idx| line.char(idx));
+        let second_last = len_chars.checked_sub(2).map(|idx| line.char(idx));
+
+        match (second_last, last) {
+            (Some('\r'), Some('\n')) => Some(LineEnding::Crlf),
+            (_, Some('\n')) => Some(LineEnding::Lf),
+            (_, Some('\r')) => Some(LineEnding::Cr),
+            _ => None,
+        }
+    }
+
+    /// 文档的总行数，空文档也有一行
+    pub fn lines_len(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    /// 构建指定行的图行
+    pub fn line(&self, line_idx: LineIdx) -> Option<Line> {
+        self.line_text(line_idx)
+            .map(|text| Line::with_tab_width(&text, self.tab_width))
+    }
+
+    /// 获取指定行的图元数量
+    pub fn line_cell_count(&self, line_idx: LineIdx) -> usize {
+        self.line_text(line_idx)
+            .map_or(0, |text| text.graphemes(true).count())
+    }
+
+    /// 查找文本在文档中出现的所有位置，文本不能跨行
+    /// 返回：每处匹配的范围 [start, end)
+    pub fn find_all(&self, pattern: &str) -> Vec<(DocumentCoordinate, DocumentCoordinate)> {
+        let mut matches = Vec::new();
+        if pattern.is_empty() || pattern.contains(['\n', '\r']) {
+            return matches;
+        }
+
+        let pattern_cells = pattern.graphemes(true).count();
+        for line_idx in 0..self.lines_len() {
+            let text = self.line_text(line_idx).unwrap_or_default();
+
+            for (byte_idx, _) in text.match_indices(pattern) {
+                // 只接受落在图元边界上的匹配
+                let Some(cell_idx) = Self::byte_to_cell(&text, byte_idx) else {
+                    continue;
+                };
+                if Self::byte_to_cell(&text, byte_idx.saturating_add(pattern.len())).is_none() {
+                    continue;
+                }
+
+                matches.push((
+                    DocumentCoordinate { line_idx, cell_idx },
+                    DocumentCoordinate {
+                        line_idx,
+                        cell_idx: cell_idx.saturating_add(pattern_cells),
+                    },
+                ));
+            }
+        }
+
+        matches
+    }
+
+    /// 行内字节索引转为图元索引，字节索引不在图元边界上时返回 None
+    fn byte_to_cell(text: &str, byte_idx: usize) -> Option<usize> {
+        if byte_idx == text.len() {
+            return Some(text.graphemes(true).count());
+        }
+
+        text.grapheme_indices(true)
+            .position(|(grapheme_byte_idx, _)| grapheme_byte_idx == byte_idx)
+    }
+
+    /// 查找 at 处括号的配对括号，可以跨行；at 处不是 pairs 中的括号或找不到配对时返回 None
+    pub fn matching_bracket(
+        &self,
+        at: &DocumentCoordinate,
+        pairs: &[(char, char)],
+    ) -> Option<DocumentCoordinate> {
+        let char_idx = self.coordinate_to_char(at);
+        let char = self.rope.get_char(char_idx)?;
+
+        let mut depth: usize = 0;
+        if let Some(&(open, close)) = pairs.iter().find(|(open, _)| *open == char) {
+            let chars = self.rope.chars_at(char_idx.saturating_add(1));
+            for (offset, next) in chars.take(MAX_BRACKET_SCAN).enumerate() {
+                if next == open {
+                    depth = depth.saturating_add(1);
+                } else if next == close {
+                    if depth == 0 {
+                        let idx = char_idx.saturating_add(1).saturating_add(offset);
+                        return Some(self.char_to_coordinate(idx));
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+            }
+        } else if let Some(&(open, close)) = pairs.iter().find(|(_, close)| *close == char) {
+            let mut chars = self.rope.chars_at(char_idx).reversed();
+            for offset in 0..MAX_BRACKET_SCAN {
+                let prev = chars.next()?;
+                if prev == close {
+                    depth = depth.saturating_add(1);
+                } else if prev == open {
+                    if depth == 0 {
+                        let idx = char_idx.saturating_sub(offset.saturating_add(1));
+                        return Some(self.char_to_coordinate(idx));
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// 在指定位置插入文本
+    /// 返回：插入后文本末尾所在的位置
+    pub fn insert(&mut self, at: &DocumentCoordinate, text: &str) -> DocumentCoordinate {
+        let char_idx = self.coordinate_to_char(at);
+        self.insert_at_char(char_idx, text);
+
+        self.char_to_coordinate(char_idx.saturating_add(text.chars().count()))
+    }
+
+    /// 在字符索引处插入文本，超出范围的索引会被调整到文档末尾
+    pub fn insert_at_char(&mut self, char_idx: usize, text: &str) {
+        self.rope.insert(char_idx.min(self.rope.len_chars()), text);
+        self.version = self.version.wrapping_add(1);
+    }
+
+    /// 获取 [start, end) 范围内的文本
+    pub fn slice(&self, start: &DocumentCoordinate, end: &DocumentCoordinate) -> String {
+        let start_char_idx = self.coordinate_to_char(start);
+        let end_char_idx = self.coordinate_to_char(end);
+
+        if start_char_idx >= end_char_idx {
+            return String::new();
+        }
+
+        self.rope.slice(start_char_idx..end_char_idx).to_string()
+    }
+
+    /// 删除 [start, end) 范围内的文本
+    /// 返回：被删除的文本
+    pub fn remove(&mut self, start: &DocumentCoordinate, end: &DocumentCoordinate) -> String {
+        let start_char_idx = self.coordinate_to_char(start);
+        let end_char_idx = self.coordinate_to_char(end);
+
+        if start_char_idx >= end_char_idx {
+            return String::new();
+        }
+
+        let removed = self.slice(start, end);
+        self.remove_chars(start_char_idx, end_char_idx);
+
+        removed
+    }
+
+    /// 删除字符索引 [start, end) 范围内的文本
+    pub fn remove_chars(&mut self, start: usize, end: usize) {
+        let end = end.min(self.rope.len_chars());
+        if start >= end {
+            return;
+        }
+
+        self.rope.remove(start..end);
+        self.version = self.version.wrapping_add(1);
+    }
+
+    /// 获取指定行的文本，不包含行尾的换行符
+    fn line_text(&self, line_idx: LineIdx) -> Option<String> {
+        let text = self.rope.get_line(line_idx)?.to_string();
+
+        let content_len = text
+            .strip_suffix("\r\n")
+            .or_else(|| text.strip_suffix('\n'))
+            .or_else(|| text.strip_suffix('\r'))
+            .map_or(text.len(), str::len);
+
+        Some(text[..content_len].to_string())
+    }
+
+    /// 文档坐标转为字符索引，超出范围的坐标会被调整到最近的合法位置
+    pub fn coordinate_to_char(&self, at: &DocumentCoordinate) -> usize {
+        let line_idx = at.line_idx.min(self.lines_len().saturating_sub(1));
+        let line_start = self.rope.line_to_char(line_idx);
+
+        let chars_before: usize = self
+            .line_text(line_idx)
+            .unwrap_or_default()
+            .graphemes(true)
+            .take(at.cell_idx)
+            .map(|grapheme| grapheme.chars().count())
+            .sum();
+
+        line_start.saturating_add(chars_before)
+    }
+
+    /// 字符索引转为文档坐标
+    pub fn char_to_coordinate(&self, char_idx: usize) -> DocumentCoordinate {
+        let char_idx = char_idx.min(self.rope.len_chars());
+        let line_idx = self.rope.char_to_line(char_idx);
+        let offset = char_idx.saturating_sub(self.rope.line_to_char(line_idx));
+
+        let mut chars_before = 0;
+        let mut cell_idx = 0;
+        for grapheme in self.line_text(line_idx).unwrap_or_default().graphemes(true) {
+            if chars_before >= offset {
+                break;
+            }
+            chars_before += grapheme.chars().count();
+            cell_idx += 1;
+        }
+
+        DocumentCoordinate { line_idx, cell_idx }
+    }
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::from("")
+    }
+}
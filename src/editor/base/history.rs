@@ -0,0 +1,185 @@
+use crate::file::Encoding;
+
+/// 一次文本改动：从字符偏移 start 开始，将 removed 文本替换为 inserted 文本
+/// 撤销时反向替换，重做时正向替换；切换保存编码也作为一次改动记录，此时文本不变
+/// 位置以字符偏移记录而不是文档坐标：插入的文本可能与前面的字素合并为一个图元（如组合用音标），
+/// 此时图元坐标无法还原改动的范围
+#[derive(Clone)]
+pub struct Change {
+    // 改动的起始字符偏移
+    pub start: usize,
+    // 被删除的文本
+    pub removed: String,
+    // 被插入的文本
+    pub inserted: String,
+    // 改动前光标的字符偏移，撤销后光标回到此处
+    pub caret_before: usize,
+    // 改动后光标的字符偏移，重做后光标回到此处
+    pub caret_after: usize,
+    // 切换保存编码时，改动前后的编码和是否带 BOM
+    pub reencoding: Option<Reencoding>,
+}
+
+/// 保存编码的改动
+#[derive(Clone, Copy)]
+pub struct Reencoding {
+    pub before: (Encoding, bool),
+    pub after: (Encoding, bool),
+}
+
+impl Change {
+    /// 插入文本末尾的字符偏移
+    pub fn inserted_end(&self) -> usize {
+        self.start.saturating_add(self.inserted.chars().count())
+    }
+
+    /// 删除文本末尾的字符偏移
+    pub fn removed_end(&self) -> usize {
+        self.start.saturating_add(self.removed.chars().count())
+    }
+
+    /// 改动是否涉及换行符
+    pub fn crosses_lines(&self) -> bool {
+        self.inserted.contains(['\n', '\r']) || self.removed.contains(['\n', '\r'])
+    }
+
+    /// 尝试把紧随其后的连续输入或连续删除合并到当前改动中，合并成功返回 true
+    /// 只有光标在两次改动间没有移动过，且两次改动都不跨行时才会合并
+    fn merge(&mut self, next: &Change) -> bool {
+        if self.reencoding.is_some() || next.reencoding.is_some() {
+            return false;
+        }
+        if next.caret_before != self.caret_after || self.crosses_lines() || next.crosses_lines() {
+            return false;
+        }
+
+        let is_typing = |change: &Change| change.removed.is_empty() && !change.inserted.is_empty();
+        let is_deleting =
+            |change: &Change| change.inserted.is_empty() && !change.removed.is_empty();
+
+        if is_typing(self) && is_typing(next) && next.start == self.inserted_end() {
+            // 连续输入
+            self.inserted.push_str(&next.inserted);
+        } else if is_deleting(self) && is_deleting(next) && next.removed_end() == self.start {
+            // 连续退格
+            self.start = next.start;
+            self.removed.insert_str(0, &next.removed);
+        } else if is_deleting(self) && is_deleting(next) && next.start == self.start {
+            // 连续向后删除
+            self.removed.push_str(&next.removed);
+        } else {
+            return false;
+        }
+
+        self.caret_after = next.caret_after;
+        true
+    }
+}
+
+/// 事务：撤销和重做的最小单位，由一次或多次改动组成
+struct Transaction {
+    id: usize,
+    changes: Vec<Change>,
+}
+
+/// 编辑历史，记录文档的所有改动，用于撤销和重做
+#[derive(Default)]
+pub struct History {
+    // 可撤销的事务
+    undo_stack: Vec<Transaction>,
+    // 可重做的事务
+    redo_stack: Vec<Transaction>,
+    // 下一个事务的编号，编号 0 代表没有任何改动的文档
+    next_id: usize,
+    // 最近一次保存时，文档所处的事务编号
+    saved_id: usize,
+    // 当前打开的事务的嵌套层数，大于 0 时所有改动都归入同一个事务
+    open_depth: usize,
+}
+
+impl History {
+    /// 记录一次改动
+    pub fn record(&mut self, change: Change) {
+        self.redo_stack.clear();
+
+        if self.open_depth > 0
+            && let Some(transaction) = self.undo_stack.last_mut()
+        {
+            transaction.changes.push(change);
+            return;
+        }
+
+        // 连续输入会合并到同一个事务，但不会合并进已保存的事务，否则无法回到保存时的状态
+        if let Some(transaction) = self.undo_stack.last_mut()
+            && transaction.id != self.saved_id
+            && let [last] = transaction.changes.as_mut_slice()
+            && last.merge(&change)
+        {
+            return;
+        }
+
+        self.push_transaction(vec![change]);
+    }
+
+    /// 开启一个事务，直到对应的 commit 之前，所有改动都会作为一个整体被撤销或重做
+    pub fn begin(&mut self) {
+        if self.open_depth == 0 {
+            self.push_transaction(Vec::new());
+        }
+        self.open_depth = self.open_depth.saturating_add(1);
+    }
+
+    /// 提交当前事务，空事务会被丢弃
+    pub fn commit(&mut self) {
+        self.open_depth = self.open_depth.saturating_sub(1);
+
+        if self.open_depth == 0
+            && self
+                .undo_stack
+                .last()
+                .is_some_and(|transaction| transaction.changes.is_empty())
+        {
+            self.undo_stack.pop();
+        }
+    }
+
+    /// 弹出最近的事务用于撤销，返回其中的改动（按发生的先后顺序）
+    pub fn undo(&mut self) -> Option<Vec<Change>> {
+        let transaction = self.undo_stack.pop()?;
+        let changes = transaction.changes.clone();
+        self.redo_stack.push(transaction);
+        Some(changes)
+    }
+
+    /// 弹出最近撤销的事务用于重做，返回其中的改动（按发生的先后顺序）
+    pub fn redo(&mut self) -> Option<Vec<Change>> {
+        let transaction = self.redo_stack.pop()?;
+        let changes = transaction.changes.clone();
+        self.undo_stack.push(transaction);
+        Some(changes)
+    }
+
+    /// 将当前状态标记为已保存
+    pub fn mark_saved(&mut self) {
+        self.saved_id = self.current_id();
+    }
+
+    /// 当前状态是否与最近一次保存时不同
+    pub fn is_modified(&self) -> bool {
+        self.current_id() != self.saved_id
+    }
+
+    fn current_id(&self) -> usize {
+        self.undo_stack
+            .last()
+            .map_or(0, |transaction| transaction.id)
+    }
+
+    fn push_transaction(&mut self, changes: Vec<Change>) {
+        self.next_id = self.next_id.saturating_add(1);
+        self.undo_stack.push(Transaction {
+            id: self.next_id,
+            changes,
+        });
+    }
+}
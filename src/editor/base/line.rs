@@ -1,55 +1,68 @@
-use std::fmt;
+use crossterm::style::Attribute;
+use std::{fmt, ops::Range};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
-    editor::Cell,
+    editor::{Case, Cell},
     prelude::{CellIdx, ColIdx},
 };
 
 /// 终端在渲染文档时，是以 Line 为单位的
+#[derive(Default)]
 pub struct Line {
     cells: Vec<Cell>,
 }
 
 impl Line {
     pub fn from(str: &str) -> Self {
-        let cells = Cell::str_to_cells(str);
+        Self::with_tab_width(str, Cell::DEFAULT_TAB_WIDTH)
+    }
 
-        let mut string = String::new();
-        for cell in &cells {
-            string.push_str(cell.to_string());
-        }
+    /// 构建图行，制表符展开到下一个制表位，制表位间隔为 tab_width
+    pub fn with_tab_width(str: &str, tab_width: usize) -> Self {
+        let cells = Cell::str_to_cells(str, tab_width);
 
         Self { cells }
     }
 
-    pub fn to_string(&self) -> String {
+    /// 行中所有图元的渲染内容
+    pub fn to_display_string(&self, show_whitespace: bool) -> String {
         let mut string = String::new();
         for cell in &self.cells {
-            string.push_str(cell.to_string());
+            string.push_str(&cell.to_display(show_whitespace));
         }
         string
     }
 
-    /// 获取可见子串
-    pub fn get_visible_substr(&self, start: ColIdx, end: ColIdx) -> String {
+    /// 获取可见子串，highlights 范围内的图元反色显示
+    /// 高亮范围包含行尾（图元数量）时，在行尾多显示一个反色空格，表示换行符也被选中
+    pub fn get_visible_substr(
+        &self,
+        start: ColIdx,
+        end: ColIdx,
+        show_whitespace: bool,
+        highlights: &[Range<CellIdx>],
+    ) -> String {
         let mut result = String::new();
+        let line_width = self.width();
 
-        if start >= end || start > self.width() {
+        if start >= end || start > line_width {
             return result;
         }
 
-        let start = start.max(0);
-        let end = end.min(self.width());
+        let is_highlighted = |cell_idx| highlights.iter().any(|range| range.contains(&cell_idx));
 
         let mut cumulative_width: usize = 0;
-        for cell in &self.cells {
+        let mut in_highlight = false;
+        for (cell_idx, cell) in self.cells.iter().enumerate() {
             let cell_width = cell.cell_width();
             let cell_start = cumulative_width;
             let cell_end = cumulative_width.saturating_add(cell_width);
 
             // 调整包含条件：只要单元格与可视区域有交集就包含
             if cell_start < end && cell_end > start {
-                result.push_str(cell.to_string());
+                Self::toggle_highlight(&mut result, &mut in_highlight, is_highlighted(cell_idx));
+                result.push_str(&cell.to_display(show_whitespace));
             }
             cumulative_width = cell_end;
 
@@ -59,9 +72,28 @@ impl Line {
             }
         }
 
+        if line_width < end && is_highlighted(self.cells.len()) {
+            Self::toggle_highlight(&mut result, &mut in_highlight, true);
+            result.push(' ');
+        }
+        Self::toggle_highlight(&mut result, &mut in_highlight, false);
+
         result
     }
 
+    /// 高亮状态变化时，插入开启或关闭反色的控制序列
+    fn toggle_highlight(result: &mut String, in_highlight: &mut bool, highlight: bool) {
+        if *in_highlight != highlight {
+            let attribute = if highlight {
+                Attribute::Reverse
+            } else {
+                Attribute::NoReverse
+            };
+            result.push_str(&attribute.to_string());
+            *in_highlight = highlight;
+        }
+    }
+
     /// 行的所有图元的总终端宽度
     pub fn width(&self) -> usize {
         self.width_until(self.cells_count())
@@ -78,11 +110,112 @@ impl Line {
             .sum()
     }
 
+    /// 终端列所在的图元索引：列落在宽图元中间时，round_up 为 true 取该图元之后的索引，否则取该图元的索引；
+    /// 列超出行宽时为图元的数量
+    pub fn cell_at_col(&self, col: ColIdx, round_up: bool) -> CellIdx {
+        let mut width: usize = 0;
+        for (cell_idx, cell) in self.cells.iter().enumerate() {
+            if width >= col {
+                return cell_idx;
+            }
+
+            let next_width = width.saturating_add(cell.cell_width());
+            if next_width > col {
+                return if round_up {
+                    cell_idx.saturating_add(1)
+                } else {
+                    cell_idx
+                };
+            }
+            width = next_width;
+        }
+
+        self.cells.len()
+    }
+
     /// 一行中所有图元的数量
     pub fn cells_count(&self) -> usize {
         self.cells.len()
     }
 
+    /// 光标向左按单词移动的目标位置：跳过空白，移动到前一个单词（或标点）的开头
+    pub fn prev_word_boundary(&self, cell_idx: CellIdx) -> CellIdx {
+        let mut target = cell_idx.min(self.cells.len());
+
+        for (range, is_whitespace) in self.word_segments().into_iter().rev() {
+            if range.start >= cell_idx {
+                continue;
+            }
+            target = range.start;
+            if !is_whitespace {
+                break;
+            }
+        }
+
+        target
+    }
+
+    /// 光标向右按单词移动的目标位置：跳过空白，移动到后一个单词（或标点）的末尾
+    pub fn next_word_boundary(&self, cell_idx: CellIdx) -> CellIdx {
+        let mut target = cell_idx.min(self.cells.len());
+
+        for (range, is_whitespace) in self.word_segments() {
+            if range.end <= cell_idx {
+                continue;
+            }
+            target = range.end;
+            if !is_whitespace {
+                break;
+            }
+        }
+
+        target
+    }
+
+    /// 把 [start, end) 范围内的图元转换为指定的大小写或命名风格
+    pub fn convert_case(&self, range: Range<CellIdx>, case: Case) -> String {
+        let end = range.end.min(self.cells.len());
+        let start = range.start.min(end);
+        let graphemes: Vec<&str> = self.cells[start..end]
+            .iter()
+            .map(Cell::to_grapheme)
+            .collect();
+
+        case.convert(&graphemes)
+    }
+
+    /// 光标所在的单词的图元范围，光标紧跟在单词之后时也算；光标周围没有单词时返回 None
+    pub fn word_at(&self, cell_idx: CellIdx) -> Option<Range<CellIdx>> {
+        let segments = self.word_segments();
+        let words = || {
+            segments
+                .iter()
+                .filter(|(_, is_whitespace)| !is_whitespace)
+                .map(|(range, _)| range)
+        };
+
+        words()
+            .find(|range| range.contains(&cell_idx))
+            .or_else(|| words().find(|range| range.end == cell_idx))
+            .cloned()
+    }
+
+    /// 按 Unicode 单词边界把行划分为若干段，返回每段的图元范围，以及该段是否为空白
+    /// 单词边界总在字素边界上，因此每段都由完整的图元组成
+    fn word_segments(&self) -> Vec<(Range<CellIdx>, bool)> {
+        let text = self.to_string();
+        let mut segments = Vec::new();
+        let mut start: CellIdx = 0;
+
+        for segment in text.split_word_bounds() {
+            let end = start.saturating_add(segment.graphemes(true).count());
+            segments.push((start..end, segment.chars().all(char::is_whitespace)));
+            start = end;
+        }
+
+        segments
+    }
+
     /// 插入一个图元到行中指定位置
     pub fn insert_cell(&mut self, cell: Cell, cell_idx: CellIdx) {
         if cell_idx >= self.cells.len() {
@@ -100,7 +233,10 @@ impl Line {
     }
 
     /// 从指定图元索引位置，将行拆分为两个图元向量
-    pub fn split(&mut self, at: CellIdx) -> (Line, Line) {
+    pub fn split(&self, at: CellIdx) -> (Line, Line) {
+        // 保证拆分位置不会大于行的图元个数
+        let at = at.min(self.cells.len());
+
         let head = Line {
             cells: self.cells[..at].to_vec(),
         };
@@ -126,15 +262,9 @@ impl fmt::Display for Line {
         let string = self
             .cells
             .iter()
-            .map(|cell| cell.to_grapheme().to_string())
+            .map(|cell| cell.to_grapheme())
             .collect::<String>();
 
         write!(formatter, "{}", string)
     }
 }
-
-impl Default for Line {
-    fn default() -> Self {
-        Self { cells: Vec::new() }
-    }
-}
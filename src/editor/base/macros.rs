@@ -0,0 +1,222 @@
+use std::{collections::HashMap, env, fs, io, path::PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::file::AtomicSave;
+
+/// 宏中的一次输入
+#[derive(Clone)]
+pub enum MacroInput {
+    // 到达命令处理器的按键
+    Key(KeyEvent),
+    // 括号粘贴的文本
+    Paste(String),
+}
+
+/// 键盘宏：录制到达命令处理器的按键和粘贴的文本，回放时按同样的顺序重新分发
+/// a-z 是命名宏，保存在 $XDG_DATA_HOME/ye/macros（未设置时为 ~/.local/share/ye/macros），
+/// 文件每行一个宏：`名称 输入 输入 ...`，按键记为 `修饰键位:键码`，粘贴记为 `p:文本的 UTF-8 十六进制`
+#[derive(Default)]
+pub struct Macros {
+    // 正在录制的宏
+    recording: Option<Vec<MacroInput>>,
+    // 最近一次录制的宏
+    last: Vec<MacroInput>,
+    // 命名宏
+    named: HashMap<char, Vec<MacroInput>>,
+    // 是否正在回放宏
+    is_replaying: bool,
+}
+
+impl Macros {
+    /// 读取保存的命名宏，文件不存在或无法解析的行被忽略
+    pub fn load() -> Self {
+        let mut macros = Self::default();
+
+        let Some(contents) = Self::path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return macros;
+        };
+
+        for line in contents.lines() {
+            let mut tokens = line.split_whitespace();
+            let mut name = tokens.next().unwrap_or_default().chars();
+            let (Some(name), None) = (name.next(), name.next()) else {
+                continue;
+            };
+            if !Self::is_valid_name(name) {
+                continue;
+            }
+
+            if let Some(keys) = tokens.map(Self::decode).collect::<Option<Vec<_>>>() {
+                macros.named.insert(name, keys);
+            }
+        }
+
+        macros
+    }
+
+    /// 合法的命名宏名，数字留作回放次数
+    pub fn is_valid_name(name: char) -> bool {
+        name.is_ascii_lowercase()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.is_replaying
+    }
+
+    pub fn set_is_replaying(&mut self, is_replaying: bool) {
+        self.is_replaying = is_replaying;
+    }
+
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// 结束录制，录制的宏成为最近一次录制的宏
+    /// 返回：录制的输入数
+    pub fn stop_recording(&mut self) -> usize {
+        if let Some(keys) = self.recording.take() {
+            self.last = keys;
+        }
+        self.last.len()
+    }
+
+    /// 录制一次输入
+    pub fn record(&mut self, input: MacroInput) {
+        if let Some(inputs) = self.recording.as_mut() {
+            inputs.push(input);
+        }
+    }
+
+    /// 指定名称的命名宏，没有名称时为最近一次录制的宏，宏不存在或为空时返回 None
+    pub fn get(&self, name: Option<char>) -> Option<&[MacroInput]> {
+        let keys = match name {
+            Some(name) => self.named.get(&name)?,
+            None => &self.last,
+        };
+        (!keys.is_empty()).then_some(keys.as_slice())
+    }
+
+    /// 把最近一次录制的宏存为命名宏，并写入文件
+    pub fn save(&mut self, name: char) -> io::Result<()> {
+        self.named.insert(name, self.last.clone());
+
+        let path = Self::path().ok_or_else(|| io::Error::other("没有可用的宏文件路径"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut names: Vec<&char> = self.named.keys().collect();
+        names.sort();
+        let contents: String = names
+            .into_iter()
+            .map(|name| {
+                let keys: Vec<String> = self.named[name].iter().map(Self::encode).collect();
+                format!("{name} {}\n", keys.join(" "))
+            })
+            .collect();
+
+        AtomicSave::write(&path, contents.as_bytes(), false)
+    }
+
+    /// 宏文件的路径
+    fn path() -> Option<PathBuf> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+
+        Some(data_home.join("ye").join("macros"))
+    }
+
+    /// 按键记为 `修饰键位:键码`，字符键记为 c 加十六进制码位，以免空格等字符破坏格式
+    /// 粘贴的文本记为 `p:` 加 UTF-8 字节的十六进制
+    fn encode(input: &MacroInput) -> String {
+        let key_event = match input {
+            MacroInput::Key(key_event) => key_event,
+            MacroInput::Paste(text) => {
+                let hex: String = text.bytes().map(|byte| format!("{byte:02x}")).collect();
+                return format!("p:{hex}");
+            }
+        };
+
+        let code = match key_event.code {
+            KeyCode::Char(char) => format!("c{:x}", u32::from(char)),
+            KeyCode::F(num) => format!("F{num}"),
+            code => format!("{code:?}"),
+        };
+        format!("{:x}:{code}", key_event.modifiers.bits())
+    }
+
+    /// 解析 `encode` 记录的输入，不支持的键码或无法解析的文本返回 None
+    fn decode(token: &str) -> Option<MacroInput> {
+        let (modifiers, code) = token.split_once(':')?;
+        if modifiers == "p" {
+            let bytes = (0..code.len())
+                .step_by(2)
+                .map(|idx| u8::from_str_radix(code.get(idx..idx.saturating_add(2))?, 16).ok())
+                .collect::<Option<Vec<u8>>>()?;
+            return String::from_utf8(bytes).ok().map(MacroInput::Paste);
+        }
+
+        let modifiers = KeyModifiers::from_bits(u8::from_str_radix(modifiers, 16).ok()?)?;
+
+        let code = match code {
+            "Enter" => KeyCode::Enter,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Tab" => KeyCode::Tab,
+            "BackTab" => KeyCode::BackTab,
+            "Esc" => KeyCode::Esc,
+            "Insert" => KeyCode::Insert,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            _ => {
+                if let Some(hex) = code.strip_prefix('c') {
+                    KeyCode::Char(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?)
+                } else {
+                    KeyCode::F(code.strip_prefix('F')?.parse().ok()?)
+                }
+            }
+        };
+
+        Some(MacroInput::Key(KeyEvent::new(code, modifiers)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode_paste() {
+        let text = "a b\n中文\t";
+        let token = Macros::encode(&MacroInput::Paste(text.to_string()));
+        assert!(!token.contains(char::is_whitespace));
+
+        match Macros::decode(&token) {
+            Some(MacroInput::Paste(decoded)) => assert_eq!(decoded, text),
+            _ => panic!("粘贴的文本无法解析：{token}"),
+        }
+    }
+
+    #[test]
+    fn encode_and_decode_key() {
+        let key_event = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::CONTROL);
+        let token = Macros::encode(&MacroInput::Key(key_event));
+
+        match Macros::decode(&token) {
+            Some(MacroInput::Key(decoded)) => assert_eq!(decoded, key_event),
+            _ => panic!("按键无法解析：{token}"),
+        }
+    }
+}
@@ -1,4 +1,16 @@
+mod caret;
+mod case;
 mod cell;
+mod document;
+mod history;
 mod line;
+mod macros;
+mod registers;
+pub use caret::Caret;
+pub use case::Case;
 pub use cell::Cell;
+pub use document::Document;
+pub use history::{Change, History, Reencoding};
 pub use line::Line;
+pub use macros::{MacroInput, Macros};
+pub use registers::{Pasted, Registers, Yank};
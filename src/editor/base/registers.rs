@@ -0,0 +1,92 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::prelude::DocumentCoordinate;
+
+// 复制历史最多保留的条数
+const HISTORY_CAPACITY: usize = 32;
+
+/// 剪切或复制的一段文本
+#[derive(Clone)]
+pub struct Yank {
+    pub text: String,
+    // 是否为整行：没有选区时剪切、复制的是光标所在行，粘贴时插入到光标所在行之前
+    pub is_line: bool,
+}
+
+/// 最近一次粘贴：粘贴后没有做其他编辑时，可以换成复制历史中更早的一项
+pub struct Pasted {
+    // 粘贴文本所在的范围 [start, end)
+    pub start: DocumentCoordinate,
+    pub end: DocumentCoordinate,
+    // 粘贴的是复制历史中的第几项
+    pub history_idx: usize,
+    // 粘贴后的文档版本
+    pub version: usize,
+}
+
+/// 寄存器：保存剪切和复制的文本
+/// 每次剪切和复制都会记入复制历史，默认粘贴复制历史中最新的一项；
+/// a-z、0-9 是命名寄存器，通过 CTRL + R 指定下一次剪切、复制或粘贴所用的寄存器
+#[derive(Default)]
+pub struct Registers {
+    // 命名寄存器
+    named: HashMap<char, Yank>,
+    // 复制历史，最新的在最前
+    history: VecDeque<Yank>,
+    // 下一次剪切、复制或粘贴所用的命名寄存器
+    selected: Option<char>,
+    // 最近一次粘贴
+    pasted: Option<Pasted>,
+}
+
+impl Registers {
+    /// 合法的命名寄存器名
+    pub fn is_valid_name(name: char) -> bool {
+        name.is_ascii_lowercase() || name.is_ascii_digit()
+    }
+
+    /// 指定下一次剪切、复制或粘贴所用的命名寄存器
+    pub fn select(&mut self, name: char) {
+        self.selected = Some(name);
+    }
+
+    /// 取出指定的命名寄存器名，只生效一次
+    pub fn take_selected(&mut self) -> Option<char> {
+        self.selected.take()
+    }
+
+    /// 记录一次剪切或复制，指定了命名寄存器时同时存入该寄存器
+    pub fn yank(&mut self, name: Option<char>, yank: Yank) {
+        if let Some(name) = name {
+            self.named.insert(name, yank.clone());
+        }
+
+        self.history.push_front(yank);
+        self.history.truncate(HISTORY_CAPACITY);
+    }
+
+    /// 命名寄存器的内容
+    pub fn named(&self, name: char) -> Option<&Yank> {
+        self.named.get(&name)
+    }
+
+    /// 复制历史中的第 idx 项，0 为最新的一项
+    pub fn history(&self, idx: usize) -> Option<&Yank> {
+        self.history.get(idx)
+    }
+
+    /// 复制历史的条数
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// 记录最近一次粘贴
+    pub fn set_pasted(&mut self, pasted: Option<Pasted>) {
+        self.pasted = pasted;
+    }
+
+    /// 取出最近一次粘贴
+    pub fn take_pasted(&mut self) -> Option<Pasted> {
+        self.pasted.take()
+    }
+}
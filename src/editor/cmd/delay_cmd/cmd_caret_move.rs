@@ -12,6 +12,8 @@ pub enum CmdCaretMove {
     Right,
     Home,
     End,
+    WordLeft,
+    WordRight,
 }
 
 impl CmdCaretMove {
@@ -45,6 +47,32 @@ impl CmdCaretMove {
         }
     }
 
+    /// 光标向左移动一个单词
+    fn caret_word_left(cmd_line: &mut CmdLine) {
+        let DocumentCoordinate { line_idx, cell_idx } = *cmd_line.caret();
+
+        Self::move_caret_validly(
+            cmd_line,
+            DocumentCoordinate {
+                line_idx,
+                cell_idx: cmd_line.input().prev_word_boundary(cell_idx),
+            },
+        );
+    }
+
+    /// 光标向右移动一个单词
+    fn caret_word_right(cmd_line: &mut CmdLine) {
+        let DocumentCoordinate { line_idx, cell_idx } = *cmd_line.caret();
+
+        Self::move_caret_validly(
+            cmd_line,
+            DocumentCoordinate {
+                line_idx,
+                cell_idx: cmd_line.input().next_word_boundary(cell_idx),
+            },
+        );
+    }
+
     /// 光标移动到行首
     fn caret_home(cmd_line: &mut CmdLine) {
         Self::move_caret_validly(
@@ -99,6 +127,12 @@ impl TryFrom<KeyEvent> for CmdCaretMove {
                 KeyCode::End => Ok(Self::End),
                 _ => Err(format!("命令行光标移动不支持：{code:?}")),
             }
+        } else if modifiers == KeyModifiers::CONTROL {
+            match code {
+                KeyCode::Left => Ok(Self::WordLeft),
+                KeyCode::Right => Ok(Self::WordRight),
+                _ => Err(format!("命令行光标按单词移动不支持：{code:?}")),
+            }
         } else {
             Err(format!("命令行光标移动不支持：{modifiers:?} + {code:?}"))
         }
@@ -115,6 +149,8 @@ impl TryExecute for CmdCaretMove {
                 Self::Right => Self::caret_right(cmd_line),
                 Self::Home => Self::caret_home(cmd_line),
                 Self::End => Self::caret_end(cmd_line),
+                Self::WordLeft => Self::caret_word_left(cmd_line),
+                Self::WordRight => Self::caret_word_right(cmd_line),
             }
         }
     }
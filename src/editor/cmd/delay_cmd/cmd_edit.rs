@@ -5,7 +5,9 @@ use crate::{
         Cell, Editor,
         cmd::{
             DelayCmd, TryExecute,
-            delay_cmd::{CmdCaretMove, Find},
+            delay_cmd::{
+                CmdCaretMove, ConvertCase, Find, PlayMacro, ProcessLines, SelectRegister, SetIndent,
+            },
         },
     },
     prelude::DocumentCoordinate,
@@ -16,6 +18,8 @@ pub enum CmdEdit {
     Insert(Cell),
     Backspace,
     Delete,
+    BackspaceWord,
+    DeleteWord,
     Confirm,
 }
 
@@ -28,6 +32,13 @@ impl CmdEdit {
         CmdCaretMove::Right.execute(editor);
     }
 
+    /// 粘贴文本：命令只有一行，粘贴的换行符会被丢弃
+    pub fn paste(text: &str, editor: &mut Editor) {
+        for cell in Cell::str_to_cells(&text.replace(['\r', '\n'], ""), Cell::DEFAULT_TAB_WIDTH) {
+            Self::insert(cell, editor);
+        }
+    }
+
     /// 删除当前光标位置的前一个图元，并向左移动光标
     fn backspace(editor: &mut Editor) {
         let cmd_line = editor.mut_cmd_line();
@@ -53,12 +64,47 @@ impl CmdEdit {
         }
     }
 
+    /// 删除光标左侧到前一个单词开头的图元，并移动光标
+    fn backspace_word(editor: &mut Editor) {
+        let cmd_line = editor.mut_cmd_line();
+        let DocumentCoordinate { line_idx, cell_idx } = *cmd_line.caret();
+        let start = cmd_line.input().prev_word_boundary(cell_idx);
+
+        let input = cmd_line.mut_input();
+        for _ in start..cell_idx {
+            input.delete_cell(start);
+        }
+        cmd_line.set_caret(DocumentCoordinate {
+            line_idx,
+            cell_idx: start,
+        });
+    }
+
+    /// 删除光标右侧到后一个单词末尾的图元，不移动光标
+    fn delete_word(editor: &mut Editor) {
+        let cmd_line = editor.mut_cmd_line();
+        let DocumentCoordinate { cell_idx, .. } = *cmd_line.caret();
+        let end = cmd_line.input().next_word_boundary(cell_idx);
+
+        let input = cmd_line.mut_input();
+        for _ in cell_idx..end {
+            input.delete_cell(cell_idx);
+        }
+    }
+
     /// 执行当前命令的确认函数
     fn confirm(editor: &mut Editor) {
         let (delay_cmd, ..) = editor.delay_cmd.as_ref().unwrap();
 
         match delay_cmd {
             DelayCmd::Find => Find::Confirm.execute(editor),
+            DelayCmd::SelectRegister => SelectRegister::Confirm.execute(editor),
+            DelayCmd::ConvertCase => ConvertCase::Confirm.execute(editor),
+            DelayCmd::ProcessLines => ProcessLines::Confirm.execute(editor),
+            DelayCmd::PlayMacro => PlayMacro::Confirm.execute(editor),
+            DelayCmd::SetIndent => SetIndent::Confirm.execute(editor),
+            // 恢复命令不经过命令编辑
+            DelayCmd::Recover => {}
         }
     }
 }
@@ -79,6 +125,11 @@ impl TryFrom<KeyEvent> for CmdEdit {
             }
             (KeyCode::Backspace, KeyModifiers::NONE) => Ok(Self::Backspace),
             (KeyCode::Delete, KeyModifiers::NONE) => Ok(Self::Delete),
+            // 多数终端把 CTRL + BACKSPACE 发送为 CTRL + H
+            (KeyCode::Backspace | KeyCode::Char('h'), KeyModifiers::CONTROL) => {
+                Ok(Self::BackspaceWord)
+            }
+            (KeyCode::Delete, KeyModifiers::CONTROL) => Ok(Self::DeleteWord),
             (KeyCode::Enter, KeyModifiers::NONE) => Ok(Self::Confirm),
             _ => Err(format!(
                 "命令编辑不支持：{:?} + {:?}",
@@ -94,6 +145,8 @@ impl TryExecute for CmdEdit {
             Self::Insert(cell) => Self::insert(cell, editor),
             Self::Backspace => Self::backspace(editor),
             Self::Delete => Self::delete(editor),
+            Self::BackspaceWord => Self::backspace_word(editor),
+            Self::DeleteWord => Self::delete_word(editor),
             Self::Confirm => Self::confirm(editor),
         }
     }
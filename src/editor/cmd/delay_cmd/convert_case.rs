@@ -0,0 +1,152 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        Case, EditorError,
+        cmd::{
+            TryExecute,
+            delay_cmd::{DelayCmd, DelayCmdTrait},
+        },
+        ui::EditArea,
+    },
+    prelude::DocumentCoordinate,
+};
+
+/// CTRL + T：输入风格对应的按键，把选中的文本（没有选区时为光标所在的单词）转换为该大小写或命名风格
+pub enum ConvertCase {
+    Enable,
+    Confirm,
+}
+
+impl ConvertCase {
+    /// 转换一个光标的选区或光标所在的单词，转换后选中（或光标停在）转换后的文本末尾
+    fn convert(edit_area: &mut EditArea, case: Case) {
+        let selection = edit_area.selection();
+        let (start, end) = match selection.clone() {
+            Some(selection) => selection,
+            None => {
+                let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+                let Some(word) = edit_area
+                    .document()
+                    .line(line_idx)
+                    .and_then(|line| line.word_at(cell_idx))
+                else {
+                    return;
+                };
+
+                (
+                    DocumentCoordinate {
+                        line_idx,
+                        cell_idx: word.start,
+                    },
+                    DocumentCoordinate {
+                        line_idx,
+                        cell_idx: word.end,
+                    },
+                )
+            }
+        };
+
+        // 逐行转换图元，行与行之间保留原来的换行符
+        let mut text = String::new();
+        for line_idx in start.line_idx..=end.line_idx {
+            let line = edit_area.document().line(line_idx).unwrap_or_default();
+            let from = if line_idx == start.line_idx {
+                start.cell_idx
+            } else {
+                0
+            };
+            let to = if line_idx == end.line_idx {
+                end.cell_idx
+            } else {
+                line.cells_count()
+            };
+            text.push_str(&line.convert_case(from..to, case));
+
+            if line_idx < end.line_idx {
+                let line_end = DocumentCoordinate {
+                    line_idx,
+                    cell_idx: line.cells_count(),
+                };
+                let next_line_start = DocumentCoordinate {
+                    line_idx: line_idx.saturating_add(1),
+                    cell_idx: 0,
+                };
+                text.push_str(&edit_area.document().slice(&line_end, &next_line_start));
+            }
+        }
+
+        let is_caret_at_start = *edit_area.caret() == start;
+        let text_end = edit_area.replace_text(start.clone(), end, &text);
+
+        if selection.is_some() {
+            let (anchor, caret) = if is_caret_at_start {
+                (text_end, start)
+            } else {
+                (start, text_end)
+            };
+            edit_area.set_caret(anchor);
+            edit_area.start_selection();
+            edit_area.set_caret(caret);
+        } else {
+            edit_area.set_caret(text_end);
+        }
+    }
+}
+
+impl TryFrom<KeyEvent> for ConvertCase {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::CONTROL && code == KeyCode::Char('t') {
+            Ok(Self::Enable)
+        } else {
+            Err(format!("转换命令不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl DelayCmdTrait for ConvertCase {
+    fn enable(self, editor: &mut Editor) {
+        editor.enable_delay_cmd(DelayCmd::ConvertCase);
+        editor.mut_cmd_line().set_prompt_for_case();
+    }
+
+    /// 输入必须是单个风格按键，确认后在每个光标处转换，作为一次编辑记录，然后返回文本编辑
+    fn confirm(self, editor: &mut Editor) {
+        let input = editor.cmd_line.input().to_string();
+        let mut chars = input.chars();
+
+        editor.disable_delay_cmd();
+        editor.cmd_line.clear_input();
+
+        let case = match (chars.next(), chars.next()) {
+            (Some(key), None) => Case::from_key(key),
+            _ => None,
+        };
+        let Some(case) = case else {
+            editor.show_error(EditorError::InvalidCase(input));
+            return;
+        };
+
+        editor.mut_edit_area().transact(|edit_area| {
+            edit_area.for_each_caret(|edit_area| Self::convert(edit_area, case));
+        });
+        editor.update_status();
+        editor.mut_cmd_line().set_prompt_for_case_converted(case);
+    }
+}
+
+impl TryExecute for ConvertCase {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Enable => self.enable(editor),
+            Self::Confirm => self.confirm(editor),
+        }
+    }
+}
@@ -33,7 +33,7 @@ impl Find {
             edit_area,
             DocumentCoordinate {
                 line_idx: line_idx.saturating_sub(1),
-                cell_idx: cell_idx,
+                cell_idx,
             },
         );
     }
@@ -46,7 +46,7 @@ impl Find {
             edit_area,
             DocumentCoordinate {
                 line_idx: line_idx.saturating_add(1),
-                cell_idx: cell_idx,
+                cell_idx,
             },
         );
     }
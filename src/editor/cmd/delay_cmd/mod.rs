@@ -2,12 +2,24 @@ use crossterm::event::{KeyEvent, KeyModifiers};
 
 mod cmd_caret_move;
 mod cmd_edit;
+mod convert_case;
 mod delay_cmd_trait;
 mod find;
+mod play_macro;
+mod process_lines;
+mod recover;
+mod select_register;
+mod set_indent;
 use cmd_caret_move::CmdCaretMove;
-use cmd_edit::CmdEdit;
+pub use cmd_edit::CmdEdit;
+pub use convert_case::ConvertCase;
 use delay_cmd_trait::DelayCmdTrait;
 pub use find::Find;
+pub use play_macro::PlayMacro;
+pub use process_lines::ProcessLines;
+pub use recover::Recover;
+pub use select_register::SelectRegister;
+pub use set_indent::SetIndent;
 
 use crate::{Editor, editor::cmd::TryExecute};
 
@@ -15,6 +27,12 @@ use crate::{Editor, editor::cmd::TryExecute};
 /// 按下对应快捷键开启指定命令模式，进行命令编辑，enter 键确认命令，个别延时命令有 “其他” 这一步，例如查找命令。
 pub enum DelayCmd {
     Find,
+    Recover,
+    SelectRegister,
+    ConvertCase,
+    ProcessLines,
+    PlayMacro,
+    SetIndent,
 }
 
 impl DelayCmd {
@@ -40,12 +58,33 @@ impl DelayCmd {
 
     /// 尝试进入命令模式
     fn entry(key_event: KeyEvent, editor: &mut Editor) -> bool {
-        return Self::try_execute::<Find>(key_event, editor);
+        Self::try_execute::<Find>(key_event, editor)
+            || Self::try_execute::<SelectRegister>(key_event, editor)
+            || Self::try_execute::<ConvertCase>(key_event, editor)
+            || Self::try_execute::<ProcessLines>(key_event, editor)
+            || Self::try_execute::<PlayMacro>(key_event, editor)
+            || Self::try_execute::<SetIndent>(key_event, editor)
     }
 
     fn edit(key_event: KeyEvent, editor: &mut Editor) -> bool {
-        return Self::try_execute::<CmdEdit>(key_event, editor)
-            || Self::try_execute::<CmdCaretMove>(key_event, editor);
+        let (delay_cmd, ..) = editor.delay_cmd.as_ref().unwrap();
+
+        match delay_cmd {
+            Self::Find
+            | Self::SelectRegister
+            | Self::ConvertCase
+            | Self::ProcessLines
+            | Self::PlayMacro
+            | Self::SetIndent => {
+                Self::try_execute::<CmdEdit>(key_event, editor)
+                    || Self::try_execute::<CmdCaretMove>(key_event, editor)
+            }
+            // 等待用户选择时，忽略其他按键，避免误改文档
+            Self::Recover => {
+                Self::try_execute::<Recover>(key_event, editor);
+                true
+            }
+        }
     }
 
     /// 确认执行后，根据不同的命令模式尝试执行对应操作
@@ -53,9 +92,13 @@ impl DelayCmd {
         let (delay_cmd, ..) = editor.delay_cmd.as_ref().unwrap();
 
         match delay_cmd {
-            Self::Find => {
-                return Self::try_execute::<Find>(key_event, editor);
-            }
+            Self::Find => Self::try_execute::<Find>(key_event, editor),
+            Self::Recover
+            | Self::SelectRegister
+            | Self::ConvertCase
+            | Self::ProcessLines
+            | Self::PlayMacro
+            | Self::SetIndent => false,
         }
     }
 }
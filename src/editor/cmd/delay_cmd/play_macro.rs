@@ -0,0 +1,147 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        EditorError, MacroInput, Macros,
+        cmd::{
+            Cmd, TryExecute,
+            delay_cmd::{DelayCmd, DelayCmdTrait},
+        },
+    },
+};
+
+// 一次最多回放的次数，避免误输入时长时间无响应
+const MAX_REPLAY_TIMES: usize = 10_000;
+
+/// CTRL + P：输入宏命令
+/// 空输入或次数：回放最近录制的宏，名称[次数]：回放命名宏，>名称：把最近录制的宏保存为命名宏
+pub enum PlayMacro {
+    Enable,
+    Confirm,
+}
+
+/// 宏命令
+enum MacroOp {
+    Replay(Option<char>, usize),
+    Save(char),
+}
+
+impl MacroOp {
+    fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+
+        if let Some(name) = input.strip_prefix('>') {
+            let mut chars = name.trim().chars();
+            return match (chars.next(), chars.next()) {
+                (Some(name), None) if Macros::is_valid_name(name) => Some(Self::Save(name)),
+                _ => None,
+            };
+        }
+
+        let name = input
+            .chars()
+            .next()
+            .filter(|char| Macros::is_valid_name(*char));
+        let times = &input[name.map_or(0, char::len_utf8)..];
+        let times = if times.is_empty() {
+            1
+        } else {
+            times.parse().ok()?
+        };
+
+        (1..=MAX_REPLAY_TIMES)
+            .contains(&times)
+            .then_some(Self::Replay(name, times))
+    }
+}
+
+impl PlayMacro {
+    /// 把宏的按键和粘贴的文本逐个交给命令处理器，与实时输入走同样的命令分发
+    fn replay(editor: &mut Editor, name: Option<char>, times: usize) {
+        let Some(inputs) = editor.macros.get(name).map(<[MacroInput]>::to_vec) else {
+            editor.show_error(EditorError::EmptyMacro(name));
+            return;
+        };
+
+        editor.macros.set_is_replaying(true);
+        'replay: for _ in 0..times {
+            for input in inputs.iter() {
+                if editor.is_quit {
+                    break 'replay;
+                }
+                match input {
+                    MacroInput::Key(key_event) => Cmd::handler(*key_event, editor),
+                    MacroInput::Paste(text) => Cmd::paste_handler(text, editor),
+                }
+            }
+        }
+        editor.macros.set_is_replaying(false);
+
+        editor.update_status();
+        if editor.delay_cmd.is_none() {
+            editor.mut_cmd_line().set_prompt_for_macro_replayed(times);
+        }
+    }
+}
+
+impl TryFrom<KeyEvent> for PlayMacro {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::CONTROL && code == KeyCode::Char('p') {
+            Ok(Self::Enable)
+        } else {
+            Err(format!("宏命令不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl DelayCmdTrait for PlayMacro {
+    /// 录制或回放宏时不能回放宏，以免宏无限地回放自身
+    fn enable(self, editor: &mut Editor) {
+        if editor.macros.is_recording() || editor.macros.is_replaying() {
+            editor.show_error(EditorError::NestedMacro);
+            return;
+        }
+
+        editor.enable_delay_cmd(DelayCmd::PlayMacro);
+        editor.mut_cmd_line().set_prompt_for_macro();
+    }
+
+    /// 解析宏命令，回放或保存宏，然后返回文本编辑
+    fn confirm(self, editor: &mut Editor) {
+        let input = editor.cmd_line.input().to_string();
+
+        editor.disable_delay_cmd();
+        editor.cmd_line.clear_input();
+
+        match MacroOp::parse(&input) {
+            Some(MacroOp::Replay(name, times)) => Self::replay(editor, name, times),
+            Some(MacroOp::Save(name)) => {
+                if editor.macros.get(None).is_none() {
+                    editor.show_error(EditorError::EmptyMacro(None));
+                    return;
+                }
+                match editor.macros.save(name) {
+                    Ok(()) => editor.cmd_line.set_prompt_for_macro_saved(name),
+                    Err(err) => editor.show_error(EditorError::SaveMacro(err)),
+                }
+            }
+            None => editor.show_error(EditorError::InvalidMacro(input)),
+        }
+    }
+}
+
+impl TryExecute for PlayMacro {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Enable => self.enable(editor),
+            Self::Confirm => self.confirm(editor),
+        }
+    }
+}
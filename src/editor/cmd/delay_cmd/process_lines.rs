@@ -0,0 +1,313 @@
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    iter::Peekable,
+    mem,
+    str::Chars,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        EditorError,
+        cmd::{
+            TryExecute,
+            delay_cmd::{DelayCmd, DelayCmdTrait},
+            text_cmd::{TextLine, TextScroll},
+        },
+        ui::EditArea,
+    },
+    prelude::LineIdx,
+};
+
+/// CTRL + L：输入行命令，处理选区覆盖的所有行，没有选区时处理整个文档
+/// sort：按字典序排序，sort n：按数值排序，sort i：忽略大小写排序，sort v：自然排序（数字部分按数值比较）
+/// uniq：去除重复行，reverse：反转行序，shuffle：随机打乱，keep 模式：只保留包含模式的行，drop 模式：删除包含模式的行
+pub enum ProcessLines {
+    Enable,
+    Confirm,
+}
+
+/// 行命令
+enum LineOp {
+    Sort,
+    SortNumeric,
+    SortCaseInsensitive,
+    SortNatural,
+    Unique,
+    Reverse,
+    Shuffle,
+    Keep(String),
+    Drop(String),
+}
+
+impl LineOp {
+    fn parse(input: &str) -> Option<Self> {
+        let (name, arg) = input
+            .trim()
+            .split_once(' ')
+            .map_or((input.trim(), ""), |(name, arg)| (name, arg.trim()));
+
+        match (name, arg) {
+            ("sort", "") => Some(Self::Sort),
+            ("sort", "n") => Some(Self::SortNumeric),
+            ("sort", "i") => Some(Self::SortCaseInsensitive),
+            ("sort", "v") => Some(Self::SortNatural),
+            ("uniq", "") => Some(Self::Unique),
+            ("reverse", "") => Some(Self::Reverse),
+            ("shuffle", "") => Some(Self::Shuffle),
+            ("keep", pattern) if !pattern.is_empty() => Some(Self::Keep(pattern.to_string())),
+            ("drop", pattern) if !pattern.is_empty() => Some(Self::Drop(pattern.to_string())),
+            _ => None,
+        }
+    }
+
+    /// 处理行，排序都是稳定的
+    fn apply(&self, mut lines: Vec<String>) -> Vec<String> {
+        match self {
+            Self::Sort => lines.sort(),
+            Self::SortNumeric => {
+                lines.sort_by(|a, b| Self::leading_number(a).total_cmp(&Self::leading_number(b)))
+            }
+            Self::SortCaseInsensitive => lines.sort_by_key(|line| line.to_lowercase()),
+            Self::SortNatural => lines.sort_by(|a, b| Self::natural_cmp(a, b)),
+            Self::Unique => {
+                let mut seen = HashSet::new();
+                lines.retain(|line| seen.insert(line.clone()));
+            }
+            Self::Reverse => lines.reverse(),
+            Self::Shuffle => Self::shuffle(&mut lines),
+            Self::Keep(pattern) => lines.retain(|line| line.contains(pattern.as_str())),
+            Self::Drop(pattern) => lines.retain(|line| !line.contains(pattern.as_str())),
+        }
+
+        lines
+    }
+
+    /// 行首（忽略空白）的数值：可选的正负号、数字和至多一个小数点，没有数值的行视为 0
+    fn leading_number(line: &str) -> f64 {
+        let line = line.trim_start();
+        let mut has_dot = false;
+        let end = line
+            .char_indices()
+            .find(|(idx, char)| match char {
+                '0'..='9' => false,
+                '-' | '+' => *idx != 0,
+                '.' => mem::replace(&mut has_dot, true),
+                _ => true,
+            })
+            .map_or(line.len(), |(idx, _)| idx);
+
+        line[..end].trim_end_matches('.').parse().unwrap_or(0.0)
+    }
+
+    /// 自然排序：连续的数字按数值比较，其余字符逐个比较
+    /// 数值相等时，若其余部分也都相同，前导零少的在前，使 a1、a01、a001 的顺序确定
+    fn natural_cmp(a: &str, b: &str) -> Ordering {
+        let mut a = a.chars().peekable();
+        let mut b = b.chars().peekable();
+        let mut zeros_ordering = Ordering::Equal;
+
+        loop {
+            match (a.peek().copied(), b.peek().copied()) {
+                (None, None) => return zeros_ordering,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                    let x = Self::take_digits(&mut a);
+                    let y = Self::take_digits(&mut b);
+                    let (x_value, y_value) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                    // 去掉前导零后，位数多的数值大，位数相同时按字典序比较
+                    let ordering = x_value
+                        .len()
+                        .cmp(&y_value.len())
+                        .then_with(|| x_value.cmp(y_value));
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                    zeros_ordering = zeros_ordering.then_with(|| x.len().cmp(&y.len()));
+                }
+                (Some(x), Some(y)) => {
+                    if x != y {
+                        return x.cmp(&y);
+                    }
+                    a.next();
+                    b.next();
+                }
+            }
+        }
+    }
+
+    fn take_digits(chars: &mut Peekable<Chars<'_>>) -> String {
+        let mut digits = String::new();
+        while let Some(char) = chars.next_if(char::is_ascii_digit) {
+            digits.push(char);
+        }
+        digits
+    }
+
+    /// 以当前时间为种子的 xorshift 随机数打乱行序（Fisher-Yates）
+    fn shuffle(lines: &mut [String]) {
+        let mut seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64)
+            | 1;
+
+        for idx in (1..lines.len()).rev() {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let other = (seed % (idx as u64 + 1)) as usize;
+            lines.swap(idx, other);
+        }
+    }
+}
+
+impl ProcessLines {
+    /// 选区覆盖的行；没有选区时为整个文档，不包含文档末尾换行符之后的空行
+    fn line_range(edit_area: &EditArea) -> (LineIdx, LineIdx) {
+        if edit_area.selection().is_some() {
+            return TextLine::line_range(edit_area);
+        }
+
+        let mut last = edit_area.lines_len().saturating_sub(1);
+        if last > 0 && edit_area.line_cell_count(last) == 0 {
+            last = last.saturating_sub(1);
+        }
+        (0, last)
+    }
+}
+
+impl TryFrom<KeyEvent> for ProcessLines {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::CONTROL && code == KeyCode::Char('l') {
+            Ok(Self::Enable)
+        } else {
+            Err(format!("行命令不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl DelayCmdTrait for ProcessLines {
+    fn enable(self, editor: &mut Editor) {
+        editor.enable_delay_cmd(DelayCmd::ProcessLines);
+        editor.mut_cmd_line().set_prompt_for_process_lines();
+    }
+
+    /// 解析行命令，在主光标的选区（或整个文档）上执行，作为一次编辑记录，然后返回文本编辑
+    fn confirm(self, editor: &mut Editor) {
+        let input = editor.cmd_line.input().to_string();
+
+        editor.disable_delay_cmd();
+        editor.cmd_line.clear_input();
+
+        let Some(op) = LineOp::parse(&input) else {
+            editor.show_error(EditorError::InvalidLineCmd(input));
+            return;
+        };
+
+        let edit_area = editor.mut_edit_area();
+        let (first, last) = Self::line_range(edit_area);
+        let lines = TextLine::lines(edit_area, first, last);
+        let count = lines.len();
+        let lines = op.apply(lines);
+        let processed = lines.len();
+        edit_area.replace_lines(first, last, &lines);
+        TextScroll::scroll_text(edit_area);
+
+        editor.update_status();
+        editor
+            .mut_cmd_line()
+            .set_prompt_for_lines_processed(count, processed);
+    }
+}
+
+impl TryExecute for ProcessLines {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Enable => self.enable(editor),
+            Self::Confirm => self.confirm(editor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_line_commands() {
+        assert!(matches!(LineOp::parse("sort"), Some(LineOp::Sort)));
+        assert!(matches!(
+            LineOp::parse(" sort n "),
+            Some(LineOp::SortNumeric)
+        ));
+        assert!(matches!(LineOp::parse("sort v"), Some(LineOp::SortNatural)));
+        assert!(matches!(LineOp::parse("uniq"), Some(LineOp::Unique)));
+        assert!(matches!(
+            LineOp::parse("keep  a b"),
+            Some(LineOp::Keep(pattern)) if pattern == "a b"
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_commands() {
+        for input in ["", "sort x", "uniq 1", "keep", "drop ", "sorted"] {
+            assert!(LineOp::parse(input).is_none(), "{input}");
+        }
+    }
+
+    #[test]
+    fn leading_number_takes_longest_number() {
+        assert_eq!(LineOp::leading_number("  42 apples"), 42.0);
+        assert_eq!(LineOp::leading_number("-3.5x"), -3.5);
+        assert_eq!(LineOp::leading_number("1.2.3"), 1.2);
+        assert_eq!(LineOp::leading_number("7."), 7.0);
+        assert_eq!(LineOp::leading_number("5-2"), 5.0);
+    }
+
+    #[test]
+    fn leading_number_without_number_is_zero() {
+        for line in ["-", "+", ".", "", "abc", "-.x"] {
+            assert_eq!(LineOp::leading_number(line), 0.0, "{line}");
+        }
+    }
+
+    #[test]
+    fn natural_cmp_compares_numbers_by_value() {
+        assert_eq!(LineOp::natural_cmp("a2", "a10"), Ordering::Less);
+        assert_eq!(LineOp::natural_cmp("a10b", "a10a"), Ordering::Greater);
+        assert_eq!(LineOp::natural_cmp("x", "x1"), Ordering::Less);
+    }
+
+    #[test]
+    fn natural_cmp_orders_leading_zeros_last() {
+        assert_eq!(LineOp::natural_cmp("a1", "a01"), Ordering::Less);
+        assert_eq!(LineOp::natural_cmp("a01b", "a1c"), Ordering::Less);
+        assert_eq!(LineOp::natural_cmp("a01", "a01"), Ordering::Equal);
+    }
+
+    #[test]
+    fn apply_sorts_and_filters() {
+        let input = lines(&["b10", "a", "b9", "a"]);
+        assert_eq!(
+            LineOp::SortNatural.apply(input.clone()),
+            ["a", "a", "b9", "b10"]
+        );
+        assert_eq!(LineOp::Unique.apply(input.clone()), ["b10", "a", "b9"]);
+        assert_eq!(LineOp::Drop("b".into()).apply(input), ["a", "a"]);
+    }
+}
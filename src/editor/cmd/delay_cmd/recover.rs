@@ -0,0 +1,91 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        EditorError,
+        cmd::{
+            TryExecute,
+            delay_cmd::{DelayCmd, DelayCmdTrait},
+        },
+    },
+};
+
+/// 打开文件时发现遗留的交换文件，询问是否恢复：Y 恢复，N 丢弃
+/// 不作选择（ESC）时交换文件保持原样，下次打开时会再次询问
+#[derive(PartialEq, Eq)]
+pub enum Recover {
+    Enable,
+    Accept,
+    Discard,
+}
+
+impl Recover {
+    /// 用交换文件中的文本替换文档，作为一次可撤销的编辑
+    fn accept(editor: &mut Editor) {
+        match editor.swap_file.recover() {
+            Ok(text) => {
+                let edit_area = editor.mut_edit_area();
+                edit_area.replace_all(&text);
+                edit_area.set_caret(Default::default());
+                editor.update_status();
+                editor.cmd_line.set_prompt_for_recovered();
+            }
+            Err(err) => editor.show_error(EditorError::ReadSwap(err)),
+        }
+    }
+
+    /// 删除遗留的交换文件
+    fn discard(editor: &mut Editor) {
+        editor.swap_file.discard();
+        editor.cmd_line.clear_prompt_msg();
+    }
+}
+
+impl TryFrom<KeyEvent> for Recover {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        match (code, modifiers) {
+            (KeyCode::Char('y' | 'Y'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                Ok(Self::Accept)
+            }
+            (KeyCode::Char('n' | 'N'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                Ok(Self::Discard)
+            }
+            _ => Err(format!("恢复命令不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl DelayCmdTrait for Recover {
+    fn enable(self, editor: &mut Editor) {
+        editor.enable_delay_cmd(DelayCmd::Recover);
+        editor.mut_cmd_line().set_prompt_for_recover();
+    }
+
+    /// 恢复命令没有后续阶段，作出选择后直接返回文本编辑
+    fn confirm(self, editor: &mut Editor) {
+        editor.disable_delay_cmd();
+    }
+}
+
+impl TryExecute for Recover {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Enable => self.enable(editor),
+            Self::Accept => {
+                Self::accept(editor);
+                self.confirm(editor);
+            }
+            Self::Discard => {
+                Self::discard(editor);
+                self.confirm(editor);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        EditorError, Registers,
+        cmd::{
+            TryExecute,
+            delay_cmd::{DelayCmd, DelayCmdTrait},
+        },
+    },
+};
+
+/// CTRL + R：输入寄存器名，指定下一次剪切、复制或粘贴所用的命名寄存器
+pub enum SelectRegister {
+    Enable,
+    Confirm,
+}
+
+impl TryFrom<KeyEvent> for SelectRegister {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::CONTROL && code == KeyCode::Char('r') {
+            Ok(Self::Enable)
+        } else {
+            Err(format!("寄存器命令不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl DelayCmdTrait for SelectRegister {
+    fn enable(self, editor: &mut Editor) {
+        editor.enable_delay_cmd(DelayCmd::SelectRegister);
+        editor.mut_cmd_line().set_prompt_for_register();
+    }
+
+    /// 寄存器名只能是单个小写字母或数字，确认后直接返回文本编辑
+    fn confirm(self, editor: &mut Editor) {
+        let input = editor.cmd_line.input().to_string();
+        let mut chars = input.chars();
+
+        editor.disable_delay_cmd();
+        editor.cmd_line.clear_input();
+
+        match (chars.next(), chars.next()) {
+            (Some(name), None) if Registers::is_valid_name(name) => {
+                editor.registers.select(name);
+                editor.cmd_line.set_prompt_for_register_selected(name);
+            }
+            _ => editor.show_error(EditorError::InvalidRegister(input)),
+        }
+    }
+}
+
+impl TryExecute for SelectRegister {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Enable => self.enable(editor),
+            Self::Confirm => self.confirm(editor),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        EditorError,
+        cmd::{
+            TryExecute,
+            delay_cmd::{DelayCmd, DelayCmdTrait},
+        },
+    },
+};
+
+// 制表符宽度的上限
+const MAX_TAB_WIDTH: usize = 16;
+
+/// CTRL + E：设置当前文档的缩进风格，只影响本次打开的文档，不修改配置文件
+/// 输入 `[s|t][宽度]`：s 缩进时插入空格，t 插入制表符，宽度为制表符宽度，省略的部分保持不变
+pub enum SetIndent {
+    Enable,
+    Confirm,
+}
+
+impl SetIndent {
+    /// 解析缩进设置
+    /// 返回：(是否使用软制表符, 制表符宽度)，省略的部分为 None，输入不合法时返回 None
+    fn parse(input: &str) -> Option<(Option<bool>, Option<usize>)> {
+        let input = input.trim();
+        let (soft_tabs, width) = match input.chars().next()? {
+            's' => (Some(true), &input[1..]),
+            't' => (Some(false), &input[1..]),
+            _ => (None, input),
+        };
+
+        let tab_width = if width.is_empty() {
+            None
+        } else {
+            Some(
+                width
+                    .parse()
+                    .ok()
+                    .filter(|width| (1..=MAX_TAB_WIDTH).contains(width))?,
+            )
+        };
+
+        (soft_tabs.is_some() || tab_width.is_some()).then_some((soft_tabs, tab_width))
+    }
+}
+
+impl TryFrom<KeyEvent> for SetIndent {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::CONTROL && code == KeyCode::Char('e') {
+            Ok(Self::Enable)
+        } else {
+            Err(format!("缩进设置不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl DelayCmdTrait for SetIndent {
+    fn enable(self, editor: &mut Editor) {
+        editor.enable_delay_cmd(DelayCmd::SetIndent);
+        let edit_area = editor.edit_area();
+        let (tab_width, soft_tabs) = (edit_area.document().tab_width(), edit_area.is_soft_tabs());
+        editor
+            .mut_cmd_line()
+            .set_prompt_for_indent(tab_width, soft_tabs);
+    }
+
+    /// 解析缩进设置并应用到当前文档，然后返回文本编辑
+    fn confirm(self, editor: &mut Editor) {
+        let input = editor.cmd_line.input().to_string();
+
+        editor.disable_delay_cmd();
+        editor.cmd_line.clear_input();
+
+        let Some((soft_tabs, tab_width)) = Self::parse(&input) else {
+            editor.show_error(EditorError::InvalidIndent(input));
+            return;
+        };
+
+        let edit_area = editor.mut_edit_area();
+        let tab_width = tab_width.unwrap_or(edit_area.document().tab_width());
+        let soft_tabs = soft_tabs.unwrap_or(edit_area.is_soft_tabs());
+        edit_area.set_indent_style(tab_width, soft_tabs);
+
+        editor
+            .mut_cmd_line()
+            .set_prompt_for_indent_set(tab_width, soft_tabs);
+    }
+}
+
+impl TryExecute for SetIndent {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Enable => self.enable(editor),
+            Self::Confirm => self.confirm(editor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_style_and_width() {
+        assert_eq!(SetIndent::parse("s4"), Some((Some(true), Some(4))));
+        assert_eq!(SetIndent::parse(" t "), Some((Some(false), None)));
+        assert_eq!(SetIndent::parse("8"), Some((None, Some(8))));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_input() {
+        for input in ["", "x", "s0", "t17", "s 4x"] {
+            assert_eq!(SetIndent::parse(input), None, "{input}");
+        }
+    }
+}
@@ -2,7 +2,7 @@ use crossterm::event::{KeyCode, KeyEvent};
 
 use crate::{Editor, editor::cmd::TryExecute};
 
-/// ESC：退出命令模式以及命令编辑模式，返回至文本编辑模式
+/// ESC：退出命令模式以及命令编辑模式，返回至文本编辑模式；文本编辑模式下取消选区和多余的光标
 #[derive(PartialEq, Eq)]
 pub struct Disable {}
 
@@ -26,6 +26,9 @@ impl TryExecute for Disable {
     /// 关闭当前命令模式
     fn execute(self, editor: &mut Editor) {
         if editor.delay_cmd.is_none() {
+            let edit_area = editor.mut_edit_area();
+            edit_area.clear_selection();
+            edit_area.clear_secondary_carets();
             return;
         }
 
@@ -1,10 +1,18 @@
 mod disable;
 mod quit;
+mod reencode;
 mod save;
+mod toggle_read_only;
+mod toggle_record;
+mod toggle_whitespace;
 use crossterm::event::KeyEvent;
 use disable::Disable;
 use quit::Quit;
-use save::Save;
+use reencode::Reencode;
+pub use save::Save;
+use toggle_read_only::ToggleReadOnly;
+pub use toggle_record::ToggleRecord;
+use toggle_whitespace::ToggleWhitespace;
 
 use crate::{Editor, editor::cmd::TryExecute};
 
@@ -13,9 +21,13 @@ pub struct InstantCmd {}
 
 impl InstantCmd {
     pub fn handler(key_event: KeyEvent, editor: &mut Editor) -> bool {
-        return Self::try_execute::<Save>(key_event, editor)
+        Self::try_execute::<Save>(key_event, editor)
             || Self::try_execute::<Quit>(key_event, editor)
-            || Self::try_execute::<Disable>(key_event, editor);
+            || Self::try_execute::<Disable>(key_event, editor)
+            || Self::try_execute::<ToggleWhitespace>(key_event, editor)
+            || Self::try_execute::<Reencode>(key_event, editor)
+            || Self::try_execute::<ToggleReadOnly>(key_event, editor)
+            || Self::try_execute::<ToggleRecord>(key_event, editor)
     }
 }
 
@@ -23,8 +23,9 @@ impl TryFrom<KeyEvent> for Quit {
 }
 
 impl TryExecute for Quit {
-    /// 清理屏幕并退出
+    /// 清理屏幕并退出，正常退出时删除本进程的交换文件
     fn execute(self, editor: &mut Editor) {
+        editor.swap_file.remove();
         Terminal::clear_screen();
         editor.set_is_quit(true);
     }
@@ -0,0 +1,36 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{Editor, editor::cmd::TryExecute};
+
+/// ALT + E：切换文档保存时使用的字符编码
+/// 按 UTF-8 -> UTF-16LE -> UTF-16BE -> GB18030 -> Latin-1 -> UTF-8 的顺序切换
+/// 切换编码作为一次编辑，可以撤销，文档变为未保存
+#[derive(PartialEq, Eq)]
+pub struct Reencode {}
+
+impl TryFrom<KeyEvent> for Reencode {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::ALT && code == KeyCode::Char('e') {
+            Ok(Reencode {})
+        } else {
+            Err(format!("切换编码不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for Reencode {
+    fn execute(self, editor: &mut Editor) {
+        let edit_area = editor.mut_edit_area();
+        let encoding = edit_area.document().format().encoding.next();
+        edit_area.set_encoding(encoding, encoding.default_bom());
+
+        editor.update_status();
+        editor.mut_cmd_line().set_prompt_for_encoding(encoding);
+    }
+}
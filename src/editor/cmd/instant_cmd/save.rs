@@ -1,9 +1,8 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use std::fs::File;
-use std::io::Write;
 
 use crate::Editor;
-use crate::editor::cmd::TryExecute;
+use crate::editor::{EditorError, cmd::TryExecute};
+use crate::file::AtomicSave;
 
 /// CRTL + S：保存文件，将文本写入硬盘
 #[derive(PartialEq, Eq)]
@@ -28,22 +27,29 @@ impl TryFrom<KeyEvent> for Save {
 impl TryExecute for Save {
     /// 保存文件，若是文件不存在就创建文件后再保存
     fn execute(self, editor: &mut Editor) {
-        let file_path = editor.file_info.get_path();
+        // 只读文档不允许保存，避免写坏二进制文件或用读取失败的空文档覆盖原文件
+        if let Some(read_only) = editor.edit_area().read_only() {
+            editor.show_error(EditorError::SaveReadOnly(read_only));
+            return;
+        }
 
-        let mut file = match File::create(file_path) {
-            Ok(f) => f, // 创建成功，获取文件句柄
-            Err(e) => {
-                panic!("创建文件失败: {e:?}");
-            }
+        // 先按文档的编码转为字节，编码失败时不触碰磁盘上的文件
+        let Some(bytes) = editor.edit_area().document().to_bytes() else {
+            let encoding = editor.edit_area().document().format().encoding;
+            editor.show_error(EditorError::Unencodable(encoding));
+            return;
         };
 
-        let edit_area = editor.mut_edit_area();
-        for line in edit_area.lines() {
-            if let Err(e) = writeln!(file, "{line}") {
-                panic!("写入文件失败: {e:?}");
-            }
+        let file_path = editor.file_info.get_path();
+
+        // 原子写入：失败时磁盘上的文件保持原样，文档内容也不受影响
+        if let Err(err) = AtomicSave::write(file_path, &bytes, editor.config.backup) {
+            editor.show_error(EditorError::SaveFile(err));
+            return;
         }
-        edit_area.set_is_modified(false);
+        editor.mut_edit_area().mark_saved();
+        // 修改已落盘，交换文件不再需要
+        editor.swap_file.remove();
 
         editor.update_status();
 
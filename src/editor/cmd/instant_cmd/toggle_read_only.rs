@@ -0,0 +1,45 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{EditorError, cmd::TryExecute, ui::ReadOnly},
+};
+
+/// ALT + R：切换文档是否只读，用于强制编辑被识别为二进制的文件
+/// 读取失败或另一个进程正在编辑而只读的文档不能解除只读
+#[derive(PartialEq, Eq)]
+pub struct ToggleReadOnly {}
+
+impl TryFrom<KeyEvent> for ToggleReadOnly {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::ALT && code == KeyCode::Char('r') {
+            Ok(ToggleReadOnly {})
+        } else {
+            Err(format!("切换只读不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for ToggleReadOnly {
+    fn execute(self, editor: &mut Editor) {
+        let read_only = match editor.edit_area().read_only() {
+            None => Some(ReadOnly::Manual),
+            Some(read_only) if read_only.can_unlock() => None,
+            Some(read_only) => {
+                editor.show_error(EditorError::LockedReadOnly(read_only));
+                return;
+            }
+        };
+        let is_read_only = read_only.is_some();
+        editor.mut_edit_area().set_read_only(read_only);
+
+        editor.update_status();
+        editor.mut_cmd_line().set_prompt_for_read_only(is_read_only);
+    }
+}
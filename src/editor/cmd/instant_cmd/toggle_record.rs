@@ -0,0 +1,40 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{Editor, editor::cmd::TryExecute};
+
+/// ALT + Q：开始或结束录制键盘宏
+#[derive(PartialEq, Eq)]
+pub struct ToggleRecord {}
+
+impl TryFrom<KeyEvent> for ToggleRecord {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::ALT && code == KeyCode::Char('q') {
+            Ok(ToggleRecord {})
+        } else {
+            Err(format!("录制宏不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for ToggleRecord {
+    /// 回放宏时忽略，宏中不会录制此按键
+    fn execute(self, editor: &mut Editor) {
+        if editor.macros.is_replaying() {
+            return;
+        }
+
+        if editor.macros.is_recording() {
+            let count = editor.macros.stop_recording();
+            editor.mut_cmd_line().set_prompt_for_recorded(count);
+        } else {
+            editor.macros.start_recording();
+            editor.mut_cmd_line().set_prompt_for_recording();
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{Editor, editor::cmd::TryExecute};
+
+/// ALT + W：切换空白字符（空格、制表符）是否显示为可见符号
+#[derive(PartialEq, Eq)]
+pub struct ToggleWhitespace {}
+
+impl TryFrom<KeyEvent> for ToggleWhitespace {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::ALT && code == KeyCode::Char('w') {
+            Ok(ToggleWhitespace {})
+        } else {
+            Err(format!("切换空白显示不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for ToggleWhitespace {
+    fn execute(self, editor: &mut Editor) {
+        editor.mut_edit_area().toggle_show_whitespace();
+    }
+}
@@ -4,22 +4,48 @@ mod delay_cmd;
 mod instant_cmd;
 mod text_cmd;
 mod try_execute;
-pub use delay_cmd::DelayCmd;
+use delay_cmd::{CmdEdit, PlayMacro};
+pub use delay_cmd::{DelayCmd, Recover};
 use instant_cmd::InstantCmd;
-use text_cmd::TextCmd;
+pub use instant_cmd::Save;
+use instant_cmd::ToggleRecord;
+use text_cmd::{TextCmd, TextEdit};
 pub use try_execute::TryExecute;
 
-use crate::Editor;
+use crate::{Editor, editor::MacroInput};
 
 pub struct Cmd {}
 
 impl Cmd {
-    /// 命令处理器，
+    /// 命令处理器，录制宏时先记下按键再分发
     pub fn handler(key_event: KeyEvent, editor: &mut Editor) {
         if key_event.kind == KeyEventKind::Press {
+            if editor.macros.is_recording() && !Self::is_macro_key(key_event, editor) {
+                editor.macros.record(MacroInput::Key(key_event));
+            }
+
             let _ = InstantCmd::handler(key_event, editor)
                 || DelayCmd::handler(key_event, editor)
                 || TextCmd::handler(key_event, editor);
         }
     }
+
+    /// 录制、回放宏的按键不记入宏
+    fn is_macro_key(key_event: KeyEvent, editor: &Editor) -> bool {
+        ToggleRecord::try_from(key_event).is_ok()
+            || (editor.delay_cmd.is_none() && PlayMacro::try_from(key_event).is_ok())
+    }
+
+    /// 粘贴处理器：开启括号粘贴后，粘贴的文本整体到达，作为一次编辑插入；录制宏时记下粘贴的文本
+    pub fn paste_handler(text: &str, editor: &mut Editor) {
+        editor.macros.record(MacroInput::Paste(text.to_string()));
+
+        match editor.delay_cmd.as_ref() {
+            None => TextEdit::paste(text, editor),
+            // 等待选择是否恢复时不接受输入
+            Some((DelayCmd::Recover, _)) => {}
+            Some((_, false)) => CmdEdit::paste(text, editor),
+            Some((_, true)) => {}
+        }
+    }
 }
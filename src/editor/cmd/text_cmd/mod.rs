@@ -1,15 +1,33 @@
 use crossterm::event::KeyEvent;
 
+mod text_block_select;
 mod text_caret_move;
+mod text_clipboard;
+mod text_comment;
 mod text_edit;
+mod text_history;
+mod text_line;
+mod text_line_ending;
+mod text_multi_caret;
+mod text_reflow;
 mod text_scroll;
+mod text_select;
+use text_block_select::TextBlockSelect;
 use text_caret_move::TextCaretMove;
-use text_edit::TextEdit;
+use text_clipboard::TextClipboard;
+use text_comment::TextComment;
+pub use text_edit::TextEdit;
+use text_history::TextHistory;
+pub use text_line::TextLine;
+use text_line_ending::TextLineEnding;
+use text_multi_caret::TextMultiCaret;
+use text_reflow::TextReflow;
 pub use text_scroll::TextScroll;
+use text_select::TextSelect;
 
 use crate::{Editor, editor::cmd::TryExecute};
 
-/// 文本命令：负责执行文本编辑和文本光标移动
+/// 文本命令：负责执行文本编辑、文本光标移动、选择文本、矩形选择、多光标、行编辑、注释、重排段落、剪切复制粘贴和撤销重做
 pub struct TextCmd {}
 
 impl TextCmd {
@@ -18,7 +36,16 @@ impl TextCmd {
         if editor.delay_cmd.is_none() {
             // 编辑器处于**文本编辑**中
             return Self::try_execute::<TextEdit>(key_event, editor)
-                || Self::try_execute::<TextCaretMove>(key_event, editor);
+                || Self::try_execute::<TextCaretMove>(key_event, editor)
+                || Self::try_execute::<TextSelect>(key_event, editor)
+                || Self::try_execute::<TextBlockSelect>(key_event, editor)
+                || Self::try_execute::<TextMultiCaret>(key_event, editor)
+                || Self::try_execute::<TextLine>(key_event, editor)
+                || Self::try_execute::<TextComment>(key_event, editor)
+                || Self::try_execute::<TextReflow>(key_event, editor)
+                || Self::try_execute::<TextHistory>(key_event, editor)
+                || Self::try_execute::<TextClipboard>(key_event, editor)
+                || Self::try_execute::<TextLineEnding>(key_event, editor);
         }
 
         false
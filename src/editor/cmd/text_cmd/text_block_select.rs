@@ -0,0 +1,96 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        cmd::{TryExecute, text_cmd::TextScroll},
+        ui::EditArea,
+    },
+    prelude::{ColIdx, LineIdx},
+};
+
+/// ALT + SHIFT + 方向键：按终端列选择矩形区域，每行一个光标，之后的输入、删除、剪切、复制和粘贴作用于矩形的每一行
+pub enum TextBlockSelect {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl TextBlockSelect {
+    /// 矩形光标移动后的位置：左右移动时跨过光标所在行的一个图元（宽图元占多列），超出行尾后每次移动一列
+    fn move_cursor(&self, edit_area: &EditArea, cursor: (LineIdx, ColIdx)) -> (LineIdx, ColIdx) {
+        let (line_idx, col) = cursor;
+        let line = edit_area.document().line(line_idx).unwrap_or_default();
+
+        match self {
+            Self::Up => (line_idx.saturating_sub(1), col),
+            Self::Down => (
+                line_idx
+                    .saturating_add(1)
+                    .min(edit_area.lines_len().saturating_sub(1)),
+                col,
+            ),
+            Self::Left => {
+                if col > line.width() {
+                    return (line_idx, col.saturating_sub(1));
+                }
+                let cell_idx = line.cell_at_col(col, false);
+                let cell_start = line.width_until(cell_idx);
+                if cell_start < col {
+                    (line_idx, cell_start)
+                } else {
+                    (line_idx, line.width_until(cell_idx.saturating_sub(1)))
+                }
+            }
+            Self::Right => {
+                let cell_idx = line.cell_at_col(col, false);
+                if cell_idx < line.cells_count() {
+                    (line_idx, line.width_until(cell_idx.saturating_add(1)))
+                } else {
+                    (line_idx, col.saturating_add(1))
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<KeyEvent> for TextBlockSelect {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers != KeyModifiers::ALT | KeyModifiers::SHIFT {
+            return Err(format!("矩形选择不支持：{modifiers:?} + {code:?}"));
+        }
+
+        match code {
+            KeyCode::Up => Ok(Self::Up),
+            KeyCode::Down => Ok(Self::Down),
+            KeyCode::Left => Ok(Self::Left),
+            KeyCode::Right => Ok(Self::Right),
+            _ => Err(format!("矩形选择不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl TryExecute for TextBlockSelect {
+    /// 不在矩形选择中时，以主光标所在位置为矩形的锚点开始选择
+    fn execute(self, editor: &mut Editor) {
+        let edit_area = editor.mut_edit_area();
+
+        let (anchor, cursor) = edit_area.block_selection().unwrap_or_else(|| {
+            let caret = edit_area.caret();
+            let col = edit_area.line_cell_width_until(caret.line_idx, caret.cell_idx);
+            ((caret.line_idx, col), (caret.line_idx, col))
+        });
+        let cursor = self.move_cursor(edit_area, cursor);
+        edit_area.set_block_selection(anchor, cursor);
+
+        TextScroll::scroll_text(edit_area);
+        editor.update_status();
+    }
+}
@@ -19,6 +19,8 @@ pub enum TextCaretMove {
     End,
     PageUp,
     PageDown,
+    WordLeft,
+    WordRight,
 }
 
 /// 移动的规则：移动函数会计算出光标的“目标位置”，然后将目标位置传递给“校验函数”；
@@ -32,7 +34,7 @@ impl TextCaretMove {
             edit_area,
             DocumentCoordinate {
                 line_idx: line_idx.saturating_sub(1),
-                cell_idx: cell_idx,
+                cell_idx,
             },
         );
     }
@@ -45,7 +47,7 @@ impl TextCaretMove {
             edit_area,
             DocumentCoordinate {
                 line_idx: line_idx.saturating_add(1),
-                cell_idx: cell_idx,
+                cell_idx,
             },
         );
     }
@@ -88,6 +90,67 @@ impl TextCaretMove {
         }
     }
 
+    // 光标向左移动一个单词
+    fn caret_word_left(edit_area: &mut EditArea) {
+        let target = Self::prev_word_position(edit_area);
+        Self::move_caret_validly(edit_area, target);
+    }
+
+    // 光标向右移动一个单词
+    fn caret_word_right(edit_area: &mut EditArea) {
+        let target = Self::next_word_position(edit_area);
+        Self::move_caret_validly(edit_area, target);
+    }
+
+    /// 光标左侧前一个单词的开头，光标位于行首时为上一行的行尾
+    pub fn prev_word_position(edit_area: &EditArea) -> DocumentCoordinate {
+        let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+
+        if cell_idx == 0 {
+            let prev_line_idx = line_idx.saturating_sub(1);
+            return DocumentCoordinate {
+                line_idx: prev_line_idx,
+                cell_idx: if line_idx > 0 {
+                    edit_area.line_cell_count(prev_line_idx)
+                } else {
+                    0
+                },
+            };
+        }
+
+        DocumentCoordinate {
+            line_idx,
+            cell_idx: edit_area
+                .document()
+                .line(line_idx)
+                .map_or(0, |line| line.prev_word_boundary(cell_idx)),
+        }
+    }
+
+    /// 光标右侧后一个单词的末尾，光标位于行尾时为下一行的行首
+    pub fn next_word_position(edit_area: &EditArea) -> DocumentCoordinate {
+        let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+
+        if cell_idx >= edit_area.line_cell_count(line_idx) {
+            return if line_idx.saturating_add(1) < edit_area.lines_len() {
+                DocumentCoordinate {
+                    line_idx: line_idx.saturating_add(1),
+                    cell_idx: 0,
+                }
+            } else {
+                DocumentCoordinate { line_idx, cell_idx }
+            };
+        }
+
+        DocumentCoordinate {
+            line_idx,
+            cell_idx: edit_area
+                .document()
+                .line(line_idx)
+                .map_or(cell_idx, |line| line.next_word_boundary(cell_idx)),
+        }
+    }
+
     // 光标移动到行首
     fn caret_home(edit_area: &mut EditArea) {
         Self::move_caret_validly(
@@ -123,7 +186,7 @@ impl TextCaretMove {
             edit_area,
             DocumentCoordinate {
                 line_idx: new_line_idx,
-                cell_idx: cell_idx,
+                cell_idx,
             },
         );
     }
@@ -138,13 +201,53 @@ impl TextCaretMove {
             edit_area,
             DocumentCoordinate {
                 line_idx: new_line_idx,
-                cell_idx: cell_idx,
+                cell_idx,
             },
         );
     }
 
+    /// 将方向键等按键转为光标移动指令
+    pub fn from_code(code: KeyCode) -> Result<Self, String> {
+        match code {
+            KeyCode::Up => Ok(Self::Up),
+            KeyCode::Down => Ok(Self::Down),
+            KeyCode::Left => Ok(Self::Left),
+            KeyCode::Right => Ok(Self::Right),
+            KeyCode::Home => Ok(Self::Home),
+            KeyCode::End => Ok(Self::End),
+            KeyCode::PageUp => Ok(Self::PageUp),
+            KeyCode::PageDown => Ok(Self::PageDown),
+            _ => Err(format!("文本光标移动不支持：{code:?}")),
+        }
+    }
+
+    /// 将 CTRL + 方向键转为按单词移动光标的指令
+    pub fn from_word_code(code: KeyCode) -> Result<Self, String> {
+        match code {
+            KeyCode::Left => Ok(Self::WordLeft),
+            KeyCode::Right => Ok(Self::WordRight),
+            _ => Err(format!("文本光标按单词移动不支持：{code:?}")),
+        }
+    }
+
+    /// 按指令移动光标，不改变选区
+    pub fn move_caret(&self, edit_area: &mut EditArea) {
+        match self {
+            Self::Up => Self::caret_up(edit_area),
+            Self::Down => Self::caret_down(edit_area),
+            Self::Left => Self::caret_left(edit_area),
+            Self::Right => Self::caret_right(edit_area),
+            Self::Home => Self::caret_home(edit_area),
+            Self::End => Self::caret_end(edit_area),
+            Self::PageUp => Self::caret_page_up(edit_area),
+            Self::PageDown => Self::caret_page_down(edit_area),
+            Self::WordLeft => Self::caret_word_left(edit_area),
+            Self::WordRight => Self::caret_word_right(edit_area),
+        }
+    }
+
     /// 光标移动到指定位置，自动调整光标位置到最近的、合法的位置
-    fn move_caret_validly(edit_area: &mut EditArea, target: DocumentCoordinate) {
+    pub fn move_caret_validly(edit_area: &mut EditArea, target: DocumentCoordinate) {
         let lines_count = edit_area.lines_len();
         // 计算有效的目标位置
         let valid_line_idx = if lines_count == 0 {
@@ -177,17 +280,9 @@ impl TryFrom<KeyEvent> for TextCaretMove {
         } = event;
 
         if modifiers == KeyModifiers::NONE {
-            match code {
-                KeyCode::Up => Ok(Self::Up),
-                KeyCode::Down => Ok(Self::Down),
-                KeyCode::Left => Ok(Self::Left),
-                KeyCode::Right => Ok(Self::Right),
-                KeyCode::Home => Ok(Self::Home),
-                KeyCode::End => Ok(Self::End),
-                KeyCode::PageUp => Ok(Self::PageUp),
-                KeyCode::PageDown => Ok(Self::PageDown),
-                _ => Err(format!("文本光标移动不支持：{code:?}")),
-            }
+            Self::from_code(code)
+        } else if modifiers == KeyModifiers::CONTROL {
+            Self::from_word_code(code)
         } else {
             Err(format!("文本光标移动不支持：{modifiers:?} + {code:?}"))
         }
@@ -197,18 +292,11 @@ impl TryFrom<KeyEvent> for TextCaretMove {
 impl TryExecute for TextCaretMove {
     fn execute(self, editor: &mut Editor) {
         {
-            let edit_area = editor.mut_edit_area();
-
-            match self {
-                Self::Up => Self::caret_up(edit_area),
-                Self::Down => Self::caret_down(edit_area),
-                Self::Left => Self::caret_left(edit_area),
-                Self::Right => Self::caret_right(edit_area),
-                Self::Home => Self::caret_home(edit_area),
-                Self::End => Self::caret_end(edit_area),
-                Self::PageUp => Self::caret_page_up(edit_area),
-                Self::PageDown => Self::caret_page_down(edit_area),
-            }
+            // 每个光标一起移动，不按 SHIFT 移动光标时取消选区
+            editor.mut_edit_area().for_each_caret(|edit_area| {
+                edit_area.clear_selection();
+                self.move_caret(edit_area);
+            });
         }
 
         // 更新状态栏
@@ -0,0 +1,315 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor, Terminal,
+    editor::{
+        EditorError, Pasted, Yank,
+        cmd::{TryExecute, text_cmd::TextCaretMove},
+        ui::EditArea,
+    },
+    file::LineEnding,
+    prelude::DocumentCoordinate,
+};
+
+/// CTRL + X：剪切，CTRL + C：复制，CTRL + V：粘贴，ALT + V：把刚粘贴的文本换成复制历史中更早的一项
+/// 有选区时剪切、复制选中的文本，没有选区时剪切、复制光标所在行；复制的文本同时导出到系统剪贴板
+/// 有多个光标（如矩形选区）时，剪切、复制每个光标选中的文本，每段一行；
+/// 粘贴的行数与光标数相同时每个光标粘贴一行，否则每个光标都粘贴全部文本
+pub enum TextClipboard {
+    Cut,
+    Copy,
+    Paste,
+    PastePrevious,
+}
+
+impl TextClipboard {
+    /// 剪切或复制选中的文本，没有选区时剪切或复制光标所在行
+    fn yank(editor: &mut Editor, is_cut: bool) {
+        let name = editor.registers.take_selected();
+        let edit_area = editor.mut_edit_area();
+
+        if edit_area.has_secondary_carets() {
+            let yank = Self::yank_carets(edit_area, is_cut);
+            Self::finish_yank(editor, is_cut, yank, name);
+            return;
+        }
+
+        let (yank, start, end) = match edit_area.selection() {
+            Some((start, end)) => {
+                let text = edit_area.document().slice(&start, &end);
+                (
+                    Yank {
+                        text,
+                        is_line: false,
+                    },
+                    start,
+                    end,
+                )
+            }
+            None => Self::current_line(edit_area),
+        };
+
+        if is_cut {
+            let text_end = edit_area.replace_text(start, end, "");
+            // 剪切整行后，光标移动到行首
+            let target = if yank.is_line {
+                DocumentCoordinate {
+                    line_idx: text_end.line_idx,
+                    cell_idx: 0,
+                }
+            } else {
+                text_end
+            };
+            TextCaretMove::move_caret_validly(edit_area, target);
+        }
+
+        Self::finish_yank(editor, is_cut, yank, name);
+    }
+
+    /// 剪切或复制每个光标选中的文本，按在文档中的位置排列，每段一行
+    fn yank_carets(edit_area: &mut EditArea, is_cut: bool) -> Yank {
+        let line_ending = edit_area.document().line_ending();
+        let text = edit_area
+            .caret_ranges()
+            .iter()
+            .map(|(start, end)| edit_area.document().slice(start, end))
+            .collect::<Vec<_>>()
+            .join(line_ending.as_str());
+
+        if is_cut {
+            edit_area.for_each_caret(|edit_area| {
+                if let Some((start, end)) = edit_area.selection() {
+                    let text_end = edit_area.replace_text(start, end, "");
+                    TextCaretMove::move_caret_validly(edit_area, text_end);
+                }
+            });
+        }
+
+        Yank {
+            text,
+            is_line: false,
+        }
+    }
+
+    /// 把剪切或复制的文本导出到系统剪贴板，并记入寄存器
+    fn finish_yank(editor: &mut Editor, is_cut: bool, yank: Yank, name: Option<char>) {
+        let line_ending = editor.edit_area().document().line_ending();
+        Terminal::copy_to_clipboard(&Self::to_text(&yank, line_ending.as_str()));
+
+        editor.cmd_line.set_prompt_for_yank(is_cut, &yank, name);
+        editor.registers.yank(name, yank);
+        editor.update_status();
+    }
+
+    /// 光标所在行的文本，以及剪切整行时要删除的范围（包含一个换行符）
+    fn current_line(edit_area: &EditArea) -> (Yank, DocumentCoordinate, DocumentCoordinate) {
+        let line_idx = edit_area.caret().line_idx;
+        let line_start = DocumentCoordinate {
+            line_idx,
+            cell_idx: 0,
+        };
+        let line_end = DocumentCoordinate {
+            line_idx,
+            cell_idx: edit_area.line_cell_count(line_idx),
+        };
+        let text = edit_area.document().slice(&line_start, &line_end);
+
+        let (start, end) = if line_idx.saturating_add(1) < edit_area.lines_len() {
+            // 删除本行及其换行符
+            let next_line_start = DocumentCoordinate {
+                line_idx: line_idx.saturating_add(1),
+                cell_idx: 0,
+            };
+            (line_start, next_line_start)
+        } else if line_idx > 0 {
+            // 最后一行没有换行符，改为删除上一行的换行符
+            let prev_line_idx = line_idx.saturating_sub(1);
+            let prev_line_end = DocumentCoordinate {
+                line_idx: prev_line_idx,
+                cell_idx: edit_area.line_cell_count(prev_line_idx),
+            };
+            (prev_line_end, line_end)
+        } else {
+            (line_start, line_end)
+        };
+
+        (
+            Yank {
+                text,
+                is_line: true,
+            },
+            start,
+            end,
+        )
+    }
+
+    /// 粘贴指定寄存器或复制历史中最新的一项
+    fn paste(editor: &mut Editor) {
+        let name = editor.registers.take_selected();
+        let yank = match name {
+            Some(name) => editor.registers.named(name).cloned(),
+            None => editor.registers.history(0).cloned(),
+        };
+        let Some(yank) = yank else {
+            editor.show_error(EditorError::EmptyRegister(name));
+            return;
+        };
+
+        let edit_area = editor.mut_edit_area();
+        if edit_area.has_secondary_carets() {
+            Self::paste_carets(edit_area, &yank);
+            editor.registers.set_pasted(None);
+            editor.update_status();
+            return;
+        }
+
+        let (start, end) = Self::insert(edit_area, &yank);
+
+        // 只有粘贴复制历史时才能换成更早的一项
+        let pasted = name.is_none().then(|| Pasted {
+            start,
+            end,
+            history_idx: 0,
+            version: edit_area.document().version(),
+        });
+        editor.registers.set_pasted(pasted);
+        editor.update_status();
+    }
+
+    /// 把刚粘贴的文本换成复制历史中更早的一项，粘贴后做过其他编辑时无效
+    fn paste_previous(editor: &mut Editor) {
+        let Some(pasted) = editor.registers.take_pasted() else {
+            return;
+        };
+        if pasted.version != editor.edit_area().document().version() {
+            return;
+        }
+
+        let history_idx = pasted
+            .history_idx
+            .saturating_add(1)
+            .checked_rem(editor.registers.history_len())
+            .unwrap_or_default();
+        let Some(yank) = editor.registers.history(history_idx).cloned() else {
+            return;
+        };
+
+        let edit_area = editor.mut_edit_area();
+        let mut range = (pasted.start.clone(), pasted.end.clone());
+        edit_area.transact(|edit_area| {
+            edit_area.replace_text(pasted.start.clone(), pasted.end, "");
+            edit_area.set_caret(pasted.start);
+            range = Self::insert(edit_area, &yank);
+        });
+
+        editor.registers.set_pasted(Some(Pasted {
+            start: range.0,
+            end: range.1,
+            history_idx,
+            version: editor.edit_area().document().version(),
+        }));
+        editor.update_status();
+    }
+
+    /// 在每个光标处粘贴：文本的行数与光标数相同时，按光标在文档中的顺序每个光标粘贴一行
+    fn paste_carets(edit_area: &mut EditArea, yank: &Yank) {
+        let lines = LineEnding::split_lines(&yank.text);
+        let rank = edit_area.primary_caret_rank();
+        let count = edit_area.caret_ranges().len();
+        let is_split = !yank.is_line && lines.len() == count;
+
+        // for_each_caret 按文档顺序处理其他光标，最后处理主光标
+        let mut idx = 0;
+        edit_area.for_each_caret(|edit_area| {
+            if is_split {
+                let line_idx = if idx == count.saturating_sub(1) {
+                    rank
+                } else if idx < rank {
+                    idx
+                } else {
+                    idx.saturating_add(1)
+                };
+                let line = Yank {
+                    text: lines[line_idx].to_string(),
+                    is_line: false,
+                };
+                Self::insert(edit_area, &line);
+            } else {
+                Self::insert(edit_area, yank);
+            }
+            idx = idx.saturating_add(1);
+        });
+    }
+
+    /// 插入寄存器中的文本：有选区时替换选区；整行文本插入到光标所在行之前，光标留在原来的文本上
+    /// 返回：插入文本的范围
+    fn insert(edit_area: &mut EditArea, yank: &Yank) -> (DocumentCoordinate, DocumentCoordinate) {
+        let line_ending = edit_area.document().line_ending();
+        let text = Self::to_text(yank, line_ending.as_str());
+        let caret = edit_area.caret().clone();
+
+        if let Some((start, end)) = edit_area.selection() {
+            let text_end = edit_area.replace_text(start.clone(), end, &text);
+            TextCaretMove::move_caret_validly(edit_area, text_end.clone());
+            return (start, text_end);
+        }
+
+        if yank.is_line {
+            let start = DocumentCoordinate {
+                line_idx: caret.line_idx,
+                cell_idx: 0,
+            };
+            let text_end = edit_area.replace_text(start.clone(), start.clone(), &text);
+            TextCaretMove::move_caret_validly(
+                edit_area,
+                DocumentCoordinate {
+                    line_idx: text_end.line_idx,
+                    cell_idx: caret.cell_idx,
+                },
+            );
+            (start, text_end)
+        } else {
+            let text_end = edit_area.replace_text(caret.clone(), caret.clone(), &yank.text);
+            TextCaretMove::move_caret_validly(edit_area, text_end.clone());
+            (caret, text_end)
+        }
+    }
+
+    /// 寄存器中的文本，整行文本补上换行符
+    fn to_text(yank: &Yank, line_ending: &str) -> String {
+        if yank.is_line {
+            format!("{}{line_ending}", yank.text)
+        } else {
+            yank.text.clone()
+        }
+    }
+}
+
+impl TryFrom<KeyEvent> for TextClipboard {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        match (code, modifiers) {
+            (KeyCode::Char('x'), KeyModifiers::CONTROL) => Ok(Self::Cut),
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => Ok(Self::Copy),
+            (KeyCode::Char('v'), KeyModifiers::CONTROL) => Ok(Self::Paste),
+            (KeyCode::Char('v'), KeyModifiers::ALT) => Ok(Self::PastePrevious),
+            _ => Err(format!("剪贴板命令不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl TryExecute for TextClipboard {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Cut => Self::yank(editor, true),
+            Self::Copy => Self::yank(editor, false),
+            Self::Paste => Self::paste(editor),
+            Self::PastePrevious => Self::paste_previous(editor),
+        }
+    }
+}
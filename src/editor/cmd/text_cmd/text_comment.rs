@@ -0,0 +1,236 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    Editor,
+    editor::{
+        Caret, EditorError,
+        cmd::{TryExecute, text_cmd::TextLine},
+        ui::EditArea,
+    },
+    prelude::DocumentCoordinate,
+};
+
+/// CTRL + /：切换光标所在行（或选区覆盖的所有行）的行注释
+/// ALT + /：切换选中文本（没有选区时为光标所在行）的块注释
+/// 多数终端把 CTRL + / 发送为 CTRL + 7
+pub enum TextComment {
+    Line,
+    Block,
+}
+
+impl TextComment {
+    /// 所有非空行都已注释时取消注释，否则在最小缩进处为每个非空行加上注释符号
+    fn toggle_line(edit_area: &mut EditArea, token: &str) {
+        let (first, last) = TextLine::line_range(edit_area);
+        let lines: Vec<(usize, String)> = (first..=last)
+            .map(|line_idx| {
+                (
+                    line_idx,
+                    TextLine::lines_text(edit_area, line_idx, line_idx),
+                )
+            })
+            .collect();
+
+        // 全是空行时，空行也加上注释
+        let has_content = lines.iter().any(|(_, text)| !text.trim().is_empty());
+        let targets: Vec<&(usize, String)> = lines
+            .iter()
+            .filter(|(_, text)| !has_content || !text.trim().is_empty())
+            .collect();
+
+        let is_commented = has_content
+            && targets
+                .iter()
+                .all(|(_, text)| text.trim_start().starts_with(token));
+        let min_indent = targets
+            .iter()
+            .map(|(_, text)| Self::indent_len(text))
+            .min()
+            .unwrap_or(0);
+
+        let (anchor, position) = TextLine::anchor_and_caret(edit_area);
+        let mut caret = Caret { position, anchor };
+
+        for (line_idx, text) in targets {
+            let line_idx = *line_idx;
+            let (start, end, inserted) = if is_commented {
+                let cell_idx = Self::indent_len(text);
+                let rest = &text.trim_start()[token.len()..];
+                let len = Self::cells_len(token) + usize::from(rest.starts_with(' '));
+                (cell_idx, cell_idx.saturating_add(len), String::new())
+            } else {
+                (min_indent, min_indent, format!("{token} "))
+            };
+
+            let start = DocumentCoordinate {
+                line_idx,
+                cell_idx: start,
+            };
+            let end = DocumentCoordinate {
+                line_idx,
+                cell_idx: end,
+            };
+            let text_end = edit_area.replace_text(start.clone(), end.clone(), &inserted);
+            caret.shift(&start, &end, &text_end);
+        }
+
+        TextLine::restore_selection(edit_area, caret.anchor, caret.position);
+    }
+
+    /// 选中文本（忽略两端的空白）已被块注释包围时去掉块注释，否则用块注释包围
+    /// 没有选区时作用于光标所在行去掉缩进后的内容
+    fn toggle_block(edit_area: &mut EditArea, open: &str, close: &str) {
+        let (start, end) = edit_area.selection().unwrap_or_else(|| {
+            let line_idx = edit_area.caret().line_idx;
+            let text = TextLine::lines_text(edit_area, line_idx, line_idx);
+            (
+                DocumentCoordinate {
+                    line_idx,
+                    cell_idx: Self::indent_len(&text),
+                },
+                DocumentCoordinate {
+                    line_idx,
+                    cell_idx: edit_area.line_cell_count(line_idx),
+                },
+            )
+        });
+
+        let text = edit_area.document().slice(&start, &end);
+        let trimmed = text.trim_matches([' ', '\t']);
+        let inner_start = DocumentCoordinate {
+            line_idx: start.line_idx,
+            cell_idx: start.cell_idx.saturating_add(Self::indent_len(&text)),
+        };
+        let inner_end = DocumentCoordinate {
+            line_idx: end.line_idx,
+            cell_idx: end.cell_idx.saturating_sub(Self::trailing_len(&text)),
+        };
+
+        let is_commented = trimmed.len() >= open.len().saturating_add(close.len())
+            && trimmed.starts_with(open)
+            && trimmed.ends_with(close);
+
+        // 先改动末尾再改动开头，开头的位置不受影响
+        let edits = if is_commented {
+            [
+                (
+                    DocumentCoordinate {
+                        line_idx: inner_end.line_idx,
+                        cell_idx: inner_end.cell_idx.saturating_sub(Self::cells_len(close)),
+                    },
+                    inner_end,
+                    "",
+                ),
+                (
+                    inner_start.clone(),
+                    DocumentCoordinate {
+                        line_idx: inner_start.line_idx,
+                        cell_idx: inner_start.cell_idx.saturating_add(Self::cells_len(open)),
+                    },
+                    "",
+                ),
+            ]
+        } else {
+            [
+                (end.clone(), end, close),
+                (start.clone(), start.clone(), open),
+            ]
+        };
+
+        let (anchor, position) = TextLine::anchor_and_caret(edit_area);
+        let mut caret = Caret { position, anchor };
+        // 改动后文本的末尾
+        let mut outer = Caret::default();
+        for (idx, (start, end, text)) in edits.into_iter().enumerate() {
+            let text_end = edit_area.replace_text(start.clone(), end.clone(), text);
+            caret.shift(&start, &end, &text_end);
+            if idx == 0 {
+                outer.position = text_end;
+            } else {
+                outer.shift(&start, &end, &text_end);
+            }
+        }
+
+        // 包围选区后，选区扩展到包含块注释的起止符号
+        if let Some(anchor) = caret.anchor.as_mut()
+            && !is_commented
+        {
+            if *anchor <= caret.position {
+                (*anchor, caret.position) = (start, outer.position);
+            } else {
+                (*anchor, caret.position) = (outer.position, start);
+            }
+        }
+
+        TextLine::restore_selection(edit_area, caret.anchor, caret.position);
+    }
+
+    /// 文本开头的缩进所占的图元数
+    fn indent_len(text: &str) -> usize {
+        text.graphemes(true)
+            .take_while(|grapheme| matches!(*grapheme, " " | "\t"))
+            .count()
+    }
+
+    /// 文本末尾的空白所占的图元数
+    fn trailing_len(text: &str) -> usize {
+        text.graphemes(true)
+            .rev()
+            .take_while(|grapheme| matches!(*grapheme, " " | "\t"))
+            .count()
+    }
+
+    /// 文本所占的图元数，注释符号可能含有多字节字符，不能用字节数代替
+    fn cells_len(text: &str) -> usize {
+        text.graphemes(true).count()
+    }
+}
+
+impl TryFrom<KeyEvent> for TextComment {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        match (code, modifiers) {
+            (KeyCode::Char('/' | '7'), KeyModifiers::CONTROL) => Ok(Self::Line),
+            (KeyCode::Char('/'), KeyModifiers::ALT) => Ok(Self::Block),
+            _ => Err(format!("注释命令不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl TryExecute for TextComment {
+    /// 在每个光标处切换注释，所有改动作为一次编辑记录
+    fn execute(self, editor: &mut Editor) {
+        let file_type = editor.edit_area().file_type().clone();
+
+        match self {
+            Self::Line => {
+                let Some(token) = file_type.line_comment() else {
+                    editor.show_error(EditorError::NoLineComment(file_type));
+                    return;
+                };
+                editor.mut_edit_area().transact(|edit_area| {
+                    edit_area.for_each_caret(|edit_area| Self::toggle_line(edit_area, token));
+                });
+            }
+            Self::Block => {
+                let Some((open, close)) = file_type.block_comment() else {
+                    editor.show_error(EditorError::NoBlockComment(file_type));
+                    return;
+                };
+                editor.mut_edit_area().transact(|edit_area| {
+                    edit_area
+                        .for_each_caret(|edit_area| Self::toggle_block(edit_area, open, close));
+                });
+            }
+        }
+
+        // 更新状态栏
+        editor.update_status();
+    }
+}
@@ -3,10 +3,14 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use crate::{
     Editor,
     editor::{
-        Cell,
-        cmd::{TryExecute, text_cmd::TextCaretMove},
+        Caret, Cell,
+        cmd::{
+            TryExecute,
+            text_cmd::{TextCaretMove, TextLine, TextReflow},
+        },
+        ui::EditArea,
     },
-    prelude::DocumentCoordinate,
+    prelude::{DocumentCoordinate, LineIdx},
 };
 
 /// 文本的编辑指令
@@ -15,93 +19,416 @@ pub enum TextEdit {
     Insert(Cell),
     Backspace,
     Delete,
+    BackspaceWord,
+    DeleteWord,
+    Indent,
+    Outdent,
 }
 
 impl TextEdit {
     /// 根据当前光标位置，截断当前行，行的后一部分作为新行内容插入到下一行，并向下移动光标
-    fn enter(editor: &mut Editor) {
-        let edit_area = editor.mut_edit_area();
-        let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+    /// 新行沿用当前行的缩进；光标前是增加缩进的字符（如 `{`）时再多缩进一级，
+    /// 光标后紧跟减少缩进的字符（如 `}`）时，该字符另起一行并与当前行对齐
+    fn enter(edit_area: &mut EditArea) {
+        edit_area.transact(|edit_area| {
+            if edit_area.selection().is_some() {
+                Self::replace_selection(edit_area, "");
+            }
 
-        if let Some(line) = edit_area.mut_line_on_caret() {
-            let (head, tail) = line.split(cell_idx);
-            let lines = edit_area.mut_lines();
-            lines.remove(line_idx);
-            lines.insert(line_idx, head);
-            lines.insert(line_idx.saturating_add(1), tail);
-            edit_area.set_is_modified(true);
-            TextCaretMove::Down.execute(editor);
-            TextCaretMove::Home.execute(editor);
-        }
+            // 新行使用文档原有的换行风格
+            let line_ending = edit_area.document().line_ending();
+            let file_type = edit_area.file_type().clone();
+
+            let caret = edit_area.caret().clone();
+            let line_start = DocumentCoordinate {
+                line_idx: caret.line_idx,
+                cell_idx: 0,
+            };
+            let line_end = DocumentCoordinate {
+                line_idx: caret.line_idx,
+                cell_idx: edit_area.line_cell_count(caret.line_idx),
+            };
+            let head = edit_area.document().slice(&line_start, &caret);
+            let tail = edit_area.document().slice(&caret, &line_end);
+
+            let mut indent = head
+                .chars()
+                .take_while(|char| matches!(char, ' ' | '\t'))
+                .collect::<String>();
+            let opens = head
+                .trim_end()
+                .chars()
+                .last()
+                .is_some_and(|char| file_type.indent_triggers().contains(&char));
+            let closes = tail
+                .trim_start()
+                .chars()
+                .next()
+                .is_some_and(|char| file_type.dedent_triggers().contains(&char));
+
+            let mut text = format!("{}{indent}", line_ending.as_str());
+            if opens {
+                let outer = indent.clone();
+                indent.push_str(&edit_area.indent_unit());
+                text = format!("{}{indent}", line_ending.as_str());
+                if closes {
+                    text.push_str(line_ending.as_str());
+                    text.push_str(&outer);
+                }
+            }
+
+            edit_area.replace_text(caret.clone(), caret.clone(), &text);
+            let position = DocumentCoordinate {
+                line_idx: caret.line_idx.saturating_add(1),
+                cell_idx: indent.chars().count(),
+            };
+            TextCaretMove::move_caret_validly(edit_area, position);
+        });
     }
 
     /// 在当前光标位置插入一个图元，并向右移动光标
-    fn insert(cell: Cell, editor: &mut Editor) {
-        let edit_area = editor.mut_edit_area();
-        let DocumentCoordinate { cell_idx, .. } = *edit_area.caret();
-
-        if let Some(line) = edit_area.mut_line_on_caret() {
-            line.insert_cell(cell, cell_idx);
-            edit_area.set_is_modified(true);
-            TextCaretMove::Right.execute(editor);
+    /// 输入的右半边与光标后的字符相同时直接越过；在只有缩进的行中输入减少缩进的字符（如 `}`）时，
+    /// 先减少一级缩进；输入成对字符的左半边时，自动补全右半边；开启自动折行时，输入非空白字符后检查是否折行
+    fn insert(cell: &Cell, edit_area: &mut EditArea) {
+        if edit_area.selection().is_some()
+            || !(Self::type_over(cell, edit_area)
+                || Self::dedent(cell, edit_area)
+                || Self::insert_pair(cell, edit_area))
+        {
+            Self::replace_selection(edit_area, cell.to_grapheme());
+        }
+
+        if edit_area.is_auto_fill() && !cell.to_grapheme().trim().is_empty() {
+            TextReflow::auto_fill(edit_area);
         }
     }
 
-    /// 删除当前光标位置的前一个图元，并向左移动光标
-    fn backspace(editor: &mut Editor) {
-        let edit_area = editor.mut_edit_area();
+    /// 输入成对字符的右半边且光标后正是该字符时，光标越过它而不插入，返回是否已处理
+    fn type_over(cell: &Cell, edit_area: &mut EditArea) -> bool {
+        let Some(char) = Self::single_char(cell.to_grapheme()) else {
+            return false;
+        };
+        let is_close = edit_area
+            .file_type()
+            .auto_pairs()
+            .iter()
+            .any(|(_, close)| *close == char);
+        if !is_close || Self::char_after_caret(edit_area) != Some(char) {
+            return false;
+        }
+
         let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+        let position = DocumentCoordinate {
+            line_idx,
+            cell_idx: cell_idx.saturating_add(1),
+        };
+        TextCaretMove::move_caret_validly(edit_area, position);
+        true
+    }
+
+    /// 输入成对字符的左半边时同时插入右半边，光标停在两者之间，返回是否已处理
+    /// 光标后紧跟单词时不补全；引号紧跟在单词之后时也不补全，例如英文的撇号；生命周期的单引号不补全
+    fn insert_pair(cell: &Cell, edit_area: &mut EditArea) -> bool {
+        let Some(char) = Self::single_char(cell.to_grapheme()) else {
+            return false;
+        };
+        let pairs = edit_area.file_type().auto_pairs();
+        let Some(&(open, close)) = pairs.iter().find(|(open, _)| *open == char) else {
+            return false;
+        };
+
+        let after = Self::char_after_caret(edit_area);
+        let before = Self::char_before_caret(edit_area);
+        let after_allowed = after.is_none_or(|after| {
+            after.is_whitespace() || pairs.iter().any(|(_, close)| *close == after)
+        });
+        let before_allowed = open != close || before.is_none_or(|before| !before.is_alphanumeric());
+        if !after_allowed || !before_allowed {
+            return false;
+        }
+        if open == '\'' {
+            let caret = edit_area.caret().clone();
+            let line_start = DocumentCoordinate {
+                line_idx: caret.line_idx,
+                cell_idx: 0,
+            };
+            let before_text = edit_area.document().slice(&line_start, &caret);
+            if edit_area.file_type().starts_lifetime(&before_text) {
+                return false;
+            }
+        }
+
+        let caret = edit_area.caret().clone();
+        edit_area.replace_text(caret.clone(), caret.clone(), &format!("{open}{close}"));
+        let position = DocumentCoordinate {
+            line_idx: caret.line_idx,
+            cell_idx: caret.cell_idx.saturating_add(1),
+        };
+        TextCaretMove::move_caret_validly(edit_area, position);
+        true
+    }
+
+    /// 光标处的字符，光标在行尾或该图元不止一个字符时返回 None
+    fn char_after_caret(edit_area: &EditArea) -> Option<char> {
+        let caret = edit_area.caret().clone();
+        if caret.cell_idx >= edit_area.line_cell_count(caret.line_idx) {
+            return None;
+        }
+        let end = DocumentCoordinate {
+            line_idx: caret.line_idx,
+            cell_idx: caret.cell_idx.saturating_add(1),
+        };
+
+        Self::single_char(&edit_area.document().slice(&caret, &end))
+    }
+
+    /// 光标前的字符，光标在行首或该图元不止一个字符时返回 None
+    fn char_before_caret(edit_area: &EditArea) -> Option<char> {
+        let caret = edit_area.caret().clone();
+        let start = DocumentCoordinate {
+            line_idx: caret.line_idx,
+            cell_idx: caret.cell_idx.checked_sub(1)?,
+        };
+
+        Self::single_char(&edit_area.document().slice(&start, &caret))
+    }
+
+    /// 只由一个字符组成的文本对应的字符
+    fn single_char(text: &str) -> Option<char> {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(char), None) => Some(char),
+            _ => None,
+        }
+    }
+
+    /// 光标前只有缩进且输入的是减少缩进的字符时，去掉一级缩进后插入该字符，返回是否已处理
+    fn dedent(cell: &Cell, edit_area: &mut EditArea) -> bool {
+        let file_type = edit_area.file_type().clone();
+        let Some(char) = Self::single_char(cell.to_grapheme()) else {
+            return false;
+        };
+        if !file_type.dedent_triggers().contains(&char) {
+            return false;
+        }
 
-        // 光标在首行的行首
-        if line_idx == 0 && cell_idx == 0 {
+        let caret = edit_area.caret().clone();
+        let line_start = DocumentCoordinate {
+            line_idx: caret.line_idx,
+            cell_idx: 0,
+        };
+        let indent = edit_area.document().slice(&line_start, &caret);
+        if indent.is_empty() || !indent.chars().all(|char| matches!(char, ' ' | '\t')) {
+            return false;
+        }
+
+        // 优先去掉一个完整的缩进单位，其次是一个制表符，否则去掉剩余的空格
+        let unit = edit_area.indent_unit();
+        let dedented = indent
+            .strip_suffix(unit.as_str())
+            .or_else(|| indent.strip_suffix('\t'))
+            .unwrap_or_else(|| indent.trim_end_matches(' '));
+
+        let text = format!("{dedented}{}", cell.to_grapheme());
+        let text_end = edit_area.replace_text(line_start, caret, &text);
+        TextCaretMove::move_caret_validly(edit_area, text_end);
+        true
+    }
+
+    /// TAB：选区跨多行时整块缩进一级，否则在光标处插入缩进
+    /// 软制表符时插入空格，补齐到下一个制表位
+    fn indent(edit_area: &mut EditArea) {
+        if let Some((start, end)) = edit_area.selection()
+            && start.line_idx != end.line_idx
+        {
+            let (first, last) = TextLine::line_range(edit_area);
+            Self::shift_lines(edit_area, first, last, true);
             return;
         }
 
-        // 其他边界情况：
-        // 行首：移动光标到上一行的行尾，并合并当前行
-        // 两种边界情况可以合并处理：移动光标到上一行的行尾，然后执行 delete
-        if line_idx == edit_area.lines_len() || cell_idx == 0 {
-            TextCaretMove::Up.execute(editor);
-            TextCaretMove::End.execute(editor);
-            Self::delete(editor);
+        let unit = edit_area.indent_unit();
+        let text = if unit == "\t" {
+            unit
         } else {
-            // 一般情况
-            // 移除当前光标位置的图元
-            if let Some(line) = edit_area.mut_line_on_caret() {
-                line.delete_cell(cell_idx.saturating_sub(1));
-                edit_area.set_is_modified(true);
-                TextCaretMove::Left.execute(editor);
+            let start = edit_area
+                .selection()
+                .map_or_else(|| edit_area.caret().clone(), |(start, _)| start);
+            let col = edit_area.line_cell_width_until(start.line_idx, start.cell_idx);
+            let tab_width = edit_area.document().tab_width();
+            " ".repeat(tab_width.saturating_sub(col % tab_width))
+        };
+        Self::replace_selection(edit_area, &text);
+    }
+
+    /// SHIFT + TAB：光标所在行（或选区覆盖的所有行）减少一级缩进
+    fn outdent(edit_area: &mut EditArea) {
+        let (first, last) = TextLine::line_range(edit_area);
+        Self::shift_lines(edit_area, first, last, false);
+    }
+
+    /// 把行 [first, last] 缩进或减少一级缩进，作为一次编辑记录，光标和选区随文本移动
+    /// 缩进时跳过空行；减少缩进时去掉行首的一个制表符，或至多制表符宽度个空格
+    fn shift_lines(edit_area: &mut EditArea, first: LineIdx, last: LineIdx, is_indent: bool) {
+        let (anchor, position) = TextLine::anchor_and_caret(edit_area);
+        let mut caret = Caret { position, anchor };
+        let unit = edit_area.indent_unit();
+        let tab_width = edit_area.document().tab_width();
+
+        edit_area.transact(|edit_area| {
+            for line_idx in first..=last {
+                let start = DocumentCoordinate {
+                    line_idx,
+                    cell_idx: 0,
+                };
+                let (end, text) = if is_indent {
+                    if edit_area.line_cell_count(line_idx) == 0 {
+                        continue;
+                    }
+                    (start.clone(), unit.as_str())
+                } else {
+                    let line = TextLine::lines_text(edit_area, line_idx, line_idx);
+                    let cell_idx = if line.starts_with('\t') {
+                        1
+                    } else {
+                        line.chars()
+                            .take(tab_width)
+                            .take_while(|char| *char == ' ')
+                            .count()
+                    };
+                    if cell_idx == 0 {
+                        continue;
+                    }
+                    (DocumentCoordinate { line_idx, cell_idx }, "")
+                };
+
+                let text_end = edit_area.replace_text(start.clone(), end.clone(), text);
+                caret.shift(&start, &end, &text_end);
             }
+        });
+
+        TextLine::restore_selection(edit_area, caret.anchor, caret.position);
+    }
+
+    /// 粘贴文本：换行符统一为文档的换行风格，整段文本作为一次编辑插入每个光标处，可一步撤销
+    pub fn paste(text: &str, editor: &mut Editor) {
+        let line_ending = editor.edit_area().document().line_ending();
+        let text = line_ending.normalize(text);
+
+        editor.mut_edit_area().transact(|edit_area| {
+            edit_area.for_each_caret(|edit_area| Self::replace_selection(edit_area, &text));
+        });
+        editor.update_status();
+    }
+
+    /// 用 text 替换选中的文本，没有选区时在光标处插入，并把光标移动到文本末尾
+    fn replace_selection(edit_area: &mut EditArea, text: &str) {
+        let caret = edit_area.caret().clone();
+        let (start, end) = edit_area
+            .selection()
+            .unwrap_or_else(|| (caret.clone(), caret));
+
+        let text_end = edit_area.replace_text(start, end, text);
+        TextCaretMove::move_caret_validly(edit_area, text_end);
+    }
+
+    /// 删除当前光标位置的前一个图元，并向左移动光标；有选区时删除选中的文本
+    /// 光标位于一对空的成对字符之间时，两者一起删除
+    fn backspace(edit_area: &mut EditArea) {
+        if edit_area.selection().is_some() {
+            Self::replace_selection(edit_area, "");
+            return;
         }
+
+        let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+
+        let pair = Self::char_before_caret(edit_area).zip(Self::char_after_caret(edit_area));
+        if pair.is_some_and(|pair| edit_area.file_type().auto_pairs().contains(&pair)) {
+            let start = DocumentCoordinate {
+                line_idx,
+                cell_idx: cell_idx.saturating_sub(1),
+            };
+            let end = DocumentCoordinate {
+                line_idx,
+                cell_idx: cell_idx.saturating_add(1),
+            };
+            let text_end = edit_area.replace_text(start, end, "");
+            TextCaretMove::move_caret_validly(edit_area, text_end);
+            return;
+        }
+
+        // 边界情况：
+        // 首行的行首：无操作
+        // 行首：删除上一行末尾的换行符，即当前行合并到上一行的行尾
+        let start = if cell_idx > 0 {
+            DocumentCoordinate {
+                line_idx,
+                cell_idx: cell_idx.saturating_sub(1),
+            }
+        } else if line_idx > 0 {
+            let prev_line_idx = line_idx.saturating_sub(1);
+            DocumentCoordinate {
+                line_idx: prev_line_idx,
+                cell_idx: edit_area.line_cell_count(prev_line_idx),
+            }
+        } else {
+            return;
+        };
+
+        let caret = edit_area.caret().clone();
+        let text_end = edit_area.replace_text(start, caret, "");
+        TextCaretMove::move_caret_validly(edit_area, text_end);
     }
 
-    /// 删除当前光标位置的一个图元，不移动光标
-    fn delete(editor: &mut Editor) {
-        let edit_area = editor.mut_edit_area();
+    /// 删除当前光标位置的一个图元，不移动光标；有选区时删除选中的文本
+    fn delete(edit_area: &mut EditArea) {
+        if edit_area.selection().is_some() {
+            Self::replace_selection(edit_area, "");
+            return;
+        }
+
         let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
 
-        // 边界情况：行尾：按下 delete 需要移除并合并下一行，如何没有下一行就无操作。
-        // edit_area.remove_line() 一定会返回一个 Line，虽然可能line中没有图元，但可以统一边界操作
-        let cell_count = edit_area
-            .line_on_caret()
-            .map_or(0, |line| line.cells_count());
-
-        if cell_idx == cell_count {
-            // 移除并获取下一行
-            let next_line = edit_area.remove_line(line_idx.saturating_add(1));
-            // 合并两行
-            if let Some(line) = edit_area.mut_line_on_caret() {
-                line.merge(next_line);
-                edit_area.set_is_modified(true);
+        // 边界情况：
+        // 行尾：删除当前行末尾的换行符，即下一行合并到当前行的行尾，如果没有下一行就无操作
+        let end = if cell_idx < edit_area.line_cell_count(line_idx) {
+            DocumentCoordinate {
+                line_idx,
+                cell_idx: cell_idx.saturating_add(1),
             }
-        } else if cell_idx < cell_count {
-            // 一般情况
-            // 移除当前光标位置的图元
-            if let Some(line) = edit_area.mut_line_on_caret() {
-                line.delete_cell(cell_idx);
-                edit_area.set_is_modified(true);
+        } else if line_idx < edit_area.lines_len().saturating_sub(1) {
+            DocumentCoordinate {
+                line_idx: line_idx.saturating_add(1),
+                cell_idx: 0,
             }
+        } else {
+            return;
+        };
+
+        let caret = edit_area.caret().clone();
+        edit_area.replace_text(caret, end, "");
+    }
+
+    /// 删除光标左侧到前一个单词开头的文本；有选区时删除选中的文本
+    fn backspace_word(edit_area: &mut EditArea) {
+        let caret = edit_area.caret().clone();
+        let (start, end) = edit_area
+            .selection()
+            .unwrap_or_else(|| (TextCaretMove::prev_word_position(edit_area), caret));
+
+        let text_end = edit_area.replace_text(start, end, "");
+        TextCaretMove::move_caret_validly(edit_area, text_end);
+    }
+
+    /// 删除光标右侧到后一个单词末尾的文本，不移动光标；有选区时删除选中的文本
+    fn delete_word(edit_area: &mut EditArea) {
+        if edit_area.selection().is_some() {
+            Self::replace_selection(edit_area, "");
+            return;
         }
+
+        let caret = edit_area.caret().clone();
+        let end = TextCaretMove::next_word_position(edit_area);
+        edit_area.replace_text(caret, end, "");
     }
 }
 
@@ -114,13 +441,16 @@ impl TryFrom<KeyEvent> for TextEdit {
                 let cell = Cell::char_to_cell(char);
                 Ok(Self::Insert(cell))
             }
-            (KeyCode::Tab, KeyModifiers::NONE) => {
-                let cell = Cell::char_to_cell('\t');
-                Ok(Self::Insert(cell))
-            }
+            (KeyCode::Tab, KeyModifiers::NONE) => Ok(Self::Indent),
+            (KeyCode::BackTab, KeyModifiers::NONE | KeyModifiers::SHIFT) => Ok(Self::Outdent),
             (KeyCode::Enter, KeyModifiers::NONE) => Ok(Self::Enter),
             (KeyCode::Backspace, KeyModifiers::NONE) => Ok(Self::Backspace),
             (KeyCode::Delete, KeyModifiers::NONE) => Ok(Self::Delete),
+            // 多数终端把 CTRL + BACKSPACE 发送为 CTRL + H
+            (KeyCode::Backspace | KeyCode::Char('h'), KeyModifiers::CONTROL) => {
+                Ok(Self::BackspaceWord)
+            }
+            (KeyCode::Delete, KeyModifiers::CONTROL) => Ok(Self::DeleteWord),
             _ => Err(format!(
                 "文本编辑不支持：{:?} + {:?}",
                 event.modifiers, event.code,
@@ -130,16 +460,22 @@ impl TryFrom<KeyEvent> for TextEdit {
 }
 
 impl TryExecute for TextEdit {
+    /// 在每个光标处执行编辑
     fn execute(self, editor: &mut Editor) {
-        match self {
-            // Enter、Insert、Backspace 会移动光标，进而触发状态栏的更新
-            TextEdit::Enter => Self::enter(editor),
-            TextEdit::Insert(cell) => Self::insert(cell, editor),
-            TextEdit::Backspace => Self::backspace(editor),
-            TextEdit::Delete => {
-                Self::delete(editor);
-                editor.update_status();
-            }
-        }
+        editor
+            .mut_edit_area()
+            .for_each_caret(|edit_area| match &self {
+                TextEdit::Enter => Self::enter(edit_area),
+                TextEdit::Insert(cell) => Self::insert(cell, edit_area),
+                TextEdit::Backspace => Self::backspace(edit_area),
+                TextEdit::Delete => Self::delete(edit_area),
+                TextEdit::BackspaceWord => Self::backspace_word(edit_area),
+                TextEdit::DeleteWord => Self::delete_word(edit_area),
+                TextEdit::Indent => Self::indent(edit_area),
+                TextEdit::Outdent => Self::outdent(edit_area),
+            });
+
+        // 更新状态栏
+        editor.update_status();
     }
 }
@@ -0,0 +1,45 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::cmd::{TryExecute, text_cmd::TextScroll},
+};
+
+/// CTRL + Z：撤销，CTRL + Y：重做
+pub enum TextHistory {
+    Undo,
+    Redo,
+}
+
+impl TryFrom<KeyEvent> for TextHistory {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        match (code, modifiers) {
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => Ok(Self::Undo),
+            (KeyCode::Char('y'), KeyModifiers::CONTROL) => Ok(Self::Redo),
+            _ => Err(format!("撤销重做不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl TryExecute for TextHistory {
+    fn execute(self, editor: &mut Editor) {
+        let edit_area = editor.mut_edit_area();
+
+        let changed = match self {
+            Self::Undo => edit_area.undo(),
+            Self::Redo => edit_area.redo(),
+        };
+
+        if changed {
+            // 光标已恢复到编辑前（后）的位置，滚动文本到光标处
+            TextScroll::scroll_text(edit_area);
+            editor.update_status();
+        }
+    }
+}
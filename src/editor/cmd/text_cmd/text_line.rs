@@ -0,0 +1,320 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    Editor,
+    editor::{
+        cmd::{TryExecute, text_cmd::TextCaretMove},
+        ui::EditArea,
+    },
+    prelude::{DocumentCoordinate, LineIdx},
+};
+
+/// 以行为单位的编辑，作用于光标所在行，有选区时作用于选区覆盖的所有行
+/// ALT + 上/下：上移（下移）行
+/// CTRL + SHIFT + D：复制行到下方
+/// CTRL + SHIFT + K：删除行
+/// CTRL + J：合并下一行，并把连接处的空白压缩为一个空格
+/// 传统终端无法区分 CTRL + SHIFT + 字母 与 CTRL + 字母，因此 CTRL + D、CTRL + K 也可以使用
+pub enum TextLine {
+    MoveUp,
+    MoveDown,
+    Duplicate,
+    Delete,
+    Join,
+}
+
+impl TextLine {
+    /// 上移或下移行：与上一行（下一行）交换位置，光标和选区随行移动
+    /// 每行带着自己的换行符移动；交换到文档末尾的行没有换行符，把它的换行符让给另一侧
+    fn move_lines(edit_area: &mut EditArea, is_up: bool) {
+        let (first, last) = Self::line_range(edit_area);
+        let (upper, lower) = if is_up {
+            let Some(prev) = first.checked_sub(1) else {
+                return;
+            };
+            ((prev, prev), (first, last))
+        } else {
+            let next = last.saturating_add(1);
+            if next >= edit_area.lines_len() {
+                return;
+            }
+            ((first, last), (next, next))
+        };
+
+        let upper_text = Self::lines_text(edit_area, upper.0, upper.1);
+        let lower_text = Self::lines_text(edit_area, lower.0, lower.1);
+        let document = edit_area.document();
+        let upper_ending = document
+            .line_ending_of(upper.1)
+            .unwrap_or(document.line_ending())
+            .as_str();
+        let (text, end) = match document.line_ending_of(lower.1) {
+            Some(lower_ending) => (
+                format!(
+                    "{lower_text}{}{upper_text}{upper_ending}",
+                    lower_ending.as_str()
+                ),
+                Self::line_start(lower.1.saturating_add(1)),
+            ),
+            None => (
+                format!("{lower_text}{upper_ending}{upper_text}"),
+                Self::line_end(edit_area, lower.1),
+            ),
+        };
+        let (anchor, caret) = Self::anchor_and_caret(edit_area);
+        edit_area.replace_text(Self::line_start(upper.0), end, &text);
+
+        let shift = |position: DocumentCoordinate| DocumentCoordinate {
+            line_idx: if is_up {
+                position.line_idx.saturating_sub(1)
+            } else {
+                position.line_idx.saturating_add(1)
+            },
+            cell_idx: position.cell_idx,
+        };
+        Self::restore_selection(edit_area, anchor.map(shift), shift(caret));
+    }
+
+    /// 把行复制一份插入到下方，光标和选区移动到复制出的行上
+    /// 复制出的行沿用原行的换行符，文档末尾的行没有换行符时使用文档的换行风格
+    fn duplicate_lines(edit_area: &mut EditArea) {
+        let (first, last) = Self::line_range(edit_area);
+        let document = edit_area.document();
+        let line_ending = document
+            .line_ending_of(last)
+            .unwrap_or(document.line_ending());
+        let block = Self::lines_text(edit_area, first, last);
+
+        let (anchor, caret) = Self::anchor_and_caret(edit_area);
+        let at = Self::line_end(edit_area, last);
+        edit_area.replace_text(at.clone(), at, &format!("{}{block}", line_ending.as_str()));
+
+        let count = last.saturating_sub(first).saturating_add(1);
+        let shift = |position: DocumentCoordinate| DocumentCoordinate {
+            line_idx: position.line_idx.saturating_add(count),
+            cell_idx: position.cell_idx,
+        };
+        Self::restore_selection(edit_area, anchor.map(shift), shift(caret));
+    }
+
+    /// 删除整行（包括换行符），光标停在原位置所在的列
+    fn delete_lines(edit_area: &mut EditArea) {
+        let (first, last) = Self::line_range(edit_area);
+        let cell_idx = edit_area.caret().cell_idx;
+
+        // 优先删除行尾的换行符；删除的是最后几行时，删除前一行末尾的换行符
+        let (start, end) = if last.saturating_add(1) < edit_area.lines_len() {
+            (
+                Self::line_start(first),
+                Self::line_start(last.saturating_add(1)),
+            )
+        } else if let Some(prev) = first.checked_sub(1) {
+            (
+                Self::line_end(edit_area, prev),
+                Self::line_end(edit_area, last),
+            )
+        } else {
+            (Self::line_start(first), Self::line_end(edit_area, last))
+        };
+        edit_area.replace_text(start, end, "");
+
+        TextCaretMove::move_caret_validly(
+            edit_area,
+            DocumentCoordinate {
+                line_idx: first,
+                cell_idx,
+            },
+        );
+    }
+
+    /// 合并行：没有选区或选区只在一行内时合并下一行，否则合并选区覆盖的所有行
+    /// 连接处两侧的空白压缩为一个空格，任一侧为空行时不加空格；光标停在最后一个连接处
+    fn join_lines(edit_area: &mut EditArea) {
+        let (first, last) = Self::line_range(edit_area);
+        let joins = last.saturating_sub(first).max(1);
+
+        for _ in 0..joins {
+            if first.saturating_add(1) >= edit_area.lines_len() {
+                break;
+            }
+
+            let text = Self::lines_text(edit_area, first, first);
+            let next_text =
+                Self::lines_text(edit_area, first.saturating_add(1), first.saturating_add(1));
+
+            let head = text.trim_end();
+            let leading = next_text
+                .graphemes(true)
+                .take_while(|grapheme| grapheme.trim().is_empty())
+                .count();
+            let separator = if head.is_empty() || next_text.trim().is_empty() {
+                ""
+            } else {
+                " "
+            };
+
+            let start = DocumentCoordinate {
+                line_idx: first,
+                cell_idx: head.graphemes(true).count(),
+            };
+            let end = DocumentCoordinate {
+                line_idx: first.saturating_add(1),
+                cell_idx: leading,
+            };
+            let text_end = edit_area.replace_text(start, end, separator);
+            TextCaretMove::move_caret_validly(edit_area, text_end);
+        }
+    }
+
+    /// 光标或选区覆盖的行 [first, last]，选区恰好结束在行首时不包含该行
+    pub fn line_range(edit_area: &EditArea) -> (LineIdx, LineIdx) {
+        match edit_area.selection() {
+            Some((start, end)) => {
+                let last = if end.cell_idx == 0 && end.line_idx > start.line_idx {
+                    end.line_idx.saturating_sub(1)
+                } else {
+                    end.line_idx
+                };
+                (start.line_idx, last)
+            }
+            None => {
+                let line_idx = edit_area.caret().line_idx;
+                (line_idx, line_idx)
+            }
+        }
+    }
+
+    /// 行 [first, last] 的文本，不包括最后一行的换行符
+    pub fn lines_text(edit_area: &EditArea, first: LineIdx, last: LineIdx) -> String {
+        edit_area
+            .document()
+            .slice(&Self::line_start(first), &Self::line_end(edit_area, last))
+    }
+
+    /// 行 [first, last] 中每一行的文本，不包括换行符
+    pub fn lines(edit_area: &EditArea, first: LineIdx, last: LineIdx) -> Vec<String> {
+        (first..=last)
+            .map(|line_idx| Self::lines_text(edit_area, line_idx, line_idx))
+            .collect()
+    }
+
+    fn line_start(line_idx: LineIdx) -> DocumentCoordinate {
+        DocumentCoordinate {
+            line_idx,
+            cell_idx: 0,
+        }
+    }
+
+    fn line_end(edit_area: &EditArea, line_idx: LineIdx) -> DocumentCoordinate {
+        DocumentCoordinate {
+            line_idx,
+            cell_idx: edit_area.line_cell_count(line_idx),
+        }
+    }
+
+    /// 选区的锚点（没有选区时为 None）和光标位置
+    pub fn anchor_and_caret(
+        edit_area: &EditArea,
+    ) -> (Option<DocumentCoordinate>, DocumentCoordinate) {
+        let caret = edit_area.caret().clone();
+        let anchor = edit_area
+            .selection()
+            .map(|(start, end)| if start == caret { end } else { start });
+
+        (anchor, caret)
+    }
+
+    /// 编辑后恢复选区和光标
+    pub fn restore_selection(
+        edit_area: &mut EditArea,
+        anchor: Option<DocumentCoordinate>,
+        caret: DocumentCoordinate,
+    ) {
+        if let Some(anchor) = anchor {
+            edit_area.set_caret(anchor);
+            edit_area.start_selection();
+        }
+        TextCaretMove::move_caret_validly(edit_area, caret);
+    }
+}
+
+impl TryFrom<KeyEvent> for TextLine {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        let is_control = modifiers == KeyModifiers::CONTROL
+            || modifiers == KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+
+        match code {
+            KeyCode::Up if modifiers == KeyModifiers::ALT => Ok(Self::MoveUp),
+            KeyCode::Down if modifiers == KeyModifiers::ALT => Ok(Self::MoveDown),
+            KeyCode::Char('d' | 'D') if is_control => Ok(Self::Duplicate),
+            KeyCode::Char('k' | 'K') if is_control => Ok(Self::Delete),
+            KeyCode::Char('j') if modifiers == KeyModifiers::CONTROL => Ok(Self::Join),
+            _ => Err(format!("行编辑不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl TryExecute for TextLine {
+    /// 在每个光标处执行行编辑，所有改动作为一次编辑记录
+    fn execute(self, editor: &mut Editor) {
+        editor.mut_edit_area().transact(|edit_area| {
+            edit_area.for_each_caret(|edit_area| match self {
+                Self::MoveUp => Self::move_lines(edit_area, true),
+                Self::MoveDown => Self::move_lines(edit_area, false),
+                Self::Duplicate => Self::duplicate_lines(edit_area),
+                Self::Delete => Self::delete_lines(edit_area),
+                Self::Join => Self::join_lines(edit_area),
+            });
+        });
+
+        // 更新状态栏
+        editor.update_status();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit_area(text: &str, line_idx: LineIdx) -> EditArea {
+        let mut edit_area = EditArea::default();
+        edit_area.replace_all(text);
+        edit_area.set_caret(DocumentCoordinate {
+            line_idx,
+            cell_idx: 0,
+        });
+        edit_area
+    }
+
+    #[test]
+    fn move_lines_keeps_each_line_ending() {
+        let mut moved = edit_area("a\r\nb\nc\r\n", 1);
+        TextLine::move_lines(&mut moved, true);
+        assert_eq!(moved.document().to_text(), "b\na\r\nc\r\n");
+
+        let mut moved = edit_area("a\r\nb\nc\r\n", 1);
+        TextLine::move_lines(&mut moved, false);
+        assert_eq!(moved.document().to_text(), "a\r\nc\r\nb\n");
+    }
+
+    #[test]
+    fn move_lines_to_end_of_document() {
+        let mut moved = edit_area("a\r\nb", 0);
+        TextLine::move_lines(&mut moved, false);
+        assert_eq!(moved.document().to_text(), "b\r\na");
+    }
+
+    #[test]
+    fn duplicate_lines_keeps_line_ending() {
+        let mut duplicated = edit_area("a\r\nb\n", 0);
+        TextLine::duplicate_lines(&mut duplicated);
+        assert_eq!(duplicated.document().to_text(), "a\r\na\r\nb\n");
+    }
+}
@@ -0,0 +1,49 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::cmd::{TryExecute, text_cmd::TextCaretMove},
+};
+
+/// ALT + L：转换文档的换行风格
+/// 混用多种换行符时，统一为出现最多的那种；否则按 LF -> CRLF -> CR -> LF 的顺序切换
+pub struct TextLineEnding {}
+
+impl TryFrom<KeyEvent> for TextLineEnding {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::ALT && code == KeyCode::Char('l') {
+            Ok(TextLineEnding {})
+        } else {
+            Err(format!("换行符转换不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for TextLineEnding {
+    fn execute(self, editor: &mut Editor) {
+        let edit_area = editor.mut_edit_area();
+        let format = edit_area.document().format();
+
+        let line_ending = if format.mixed_line_endings {
+            format.line_ending
+        } else {
+            format.line_ending.next()
+        };
+
+        edit_area.convert_line_endings(line_ending);
+        // 转换后光标所在行的图元不变，校验一次光标位置即可
+        let caret = edit_area.caret().clone();
+        TextCaretMove::move_caret_validly(edit_area, caret);
+
+        editor
+            .mut_cmd_line()
+            .set_prompt_for_line_ending(line_ending);
+        editor.update_status();
+    }
+}
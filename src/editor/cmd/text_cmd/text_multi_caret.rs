@@ -0,0 +1,132 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        Caret,
+        cmd::{TryExecute, text_cmd::TextScroll},
+        ui::EditArea,
+    },
+    prelude::DocumentCoordinate,
+};
+
+/// CTRL + ALT + 上/下：在最上方（最下方）的光标的上一行（下一行）添加光标
+/// ALT + D：选中光标所在的单词（或选中的文本）在文档中的每一处匹配，每处一个光标
+pub enum TextMultiCaret {
+    AddAbove,
+    AddBelow,
+    SelectMatches,
+}
+
+impl TextMultiCaret {
+    /// 在所有光标的上方或下方添加一个光标，列位置与主光标相同，超出行尾时移到行尾
+    fn add_vertically(edit_area: &mut EditArea, is_above: bool) {
+        let positions = edit_area.caret_positions();
+        let line_idx = if is_above {
+            positions
+                .iter()
+                .map(|position| position.line_idx)
+                .min()
+                .and_then(|line_idx| line_idx.checked_sub(1))
+        } else {
+            positions
+                .iter()
+                .map(|position| position.line_idx)
+                .max()
+                .map(|line_idx| line_idx.saturating_add(1))
+                .filter(|line_idx| *line_idx < edit_area.lines_len())
+        };
+        let Some(line_idx) = line_idx else {
+            return;
+        };
+
+        let cell_idx = edit_area
+            .caret()
+            .cell_idx
+            .min(edit_area.line_cell_count(line_idx));
+        edit_area.add_caret(Caret {
+            position: DocumentCoordinate { line_idx, cell_idx },
+            anchor: None,
+        });
+    }
+
+    /// 选中每一处匹配：没有选区时先选中光标所在的单词，主光标保留在原来的匹配上
+    fn select_matches(edit_area: &mut EditArea) {
+        let (start, end) = match edit_area.selection() {
+            Some(selection) => selection,
+            None => {
+                let DocumentCoordinate { line_idx, cell_idx } = *edit_area.caret();
+                let Some(word) = edit_area
+                    .document()
+                    .line(line_idx)
+                    .and_then(|line| line.word_at(cell_idx))
+                else {
+                    return;
+                };
+
+                (
+                    DocumentCoordinate {
+                        line_idx,
+                        cell_idx: word.start,
+                    },
+                    DocumentCoordinate {
+                        line_idx,
+                        cell_idx: word.end,
+                    },
+                )
+            }
+        };
+
+        let pattern = edit_area.document().slice(&start, &end);
+        let matches = edit_area.document().find_all(&pattern);
+
+        // 主光标选中原来的匹配
+        edit_area.set_caret(start.clone());
+        edit_area.start_selection();
+        edit_area.set_caret(end);
+
+        for (match_start, match_end) in matches {
+            if match_start != start {
+                edit_area.add_caret(Caret {
+                    position: match_end,
+                    anchor: Some(match_start),
+                });
+            }
+        }
+    }
+}
+
+impl TryFrom<KeyEvent> for TextMultiCaret {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::CONTROL | KeyModifiers::ALT && code == KeyCode::Up {
+            Ok(Self::AddAbove)
+        } else if modifiers == KeyModifiers::CONTROL | KeyModifiers::ALT && code == KeyCode::Down {
+            Ok(Self::AddBelow)
+        } else if modifiers == KeyModifiers::ALT && code == KeyCode::Char('d') {
+            Ok(Self::SelectMatches)
+        } else {
+            Err(format!("多光标命令不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for TextMultiCaret {
+    fn execute(self, editor: &mut Editor) {
+        let edit_area = editor.mut_edit_area();
+
+        match self {
+            Self::AddAbove => Self::add_vertically(edit_area, true),
+            Self::AddBelow => Self::add_vertically(edit_area, false),
+            Self::SelectMatches => Self::select_matches(edit_area),
+        }
+
+        TextScroll::scroll_text(edit_area);
+        editor.update_status();
+    }
+}
@@ -0,0 +1,512 @@
+use std::iter;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::{
+        Caret, Cell,
+        cmd::{
+            TryExecute,
+            text_cmd::{TextCaretMove, TextLine, TextScroll},
+        },
+        ui::EditArea,
+    },
+    prelude::{CellIdx, DocumentCoordinate, LineIdx},
+};
+
+// 重排时保留在每行开头的注释或引用符号，较长的在前；符号后必须是空白或行尾
+const PREFIX_MARKERS: [&str; 5] = ["///", "//!", "//", "#", ">"];
+// 不能出现在行首的标点，折行时与前一个字素放在同一行
+const CLOSING_PUNCTUATION: &str = "，。、；：！？）」』》〉】”’,.;:!?)]}";
+
+/// ALT + P：把光标所在的段落（或选区覆盖的所有行）按折行宽度重排
+/// ALT + A：开启或关闭自动折行，输入时超出折行宽度的行自动在单词之间折行
+/// 段落是连续的非空行，且各行开头的缩进和注释符号相同；重排时保留首行的缩进和注释符号
+/// 列表项（`- `、`* `、`+ `、`1. `、`1) ` 开头的行）总是开始新的段落，后续行缩进到列表项的文本处
+pub enum TextReflow {
+    Paragraph,
+    ToggleAutoFill,
+}
+
+/// 折行的最小单位：一个单词、一个宽字素（如汉字），以及紧跟其后的不能出现在行首的标点
+struct Atom {
+    text: String,
+    width: usize,
+    is_wide: bool,
+    // 与前一个单位之间是否有空格
+    space_before: bool,
+}
+
+impl TextReflow {
+    /// 行首的缩进和注释符号
+    /// 返回：(用于区分段落的缩进和注释符号, 包含注释符号后空白的完整前缀)
+    fn prefix(line: &str) -> (&str, &str) {
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let rest = &line[indent_len..];
+
+        let Some(marker) = PREFIX_MARKERS.iter().find(|marker| {
+            rest.strip_prefix(**marker)
+                .is_some_and(|after| after.chars().next().is_none_or(char::is_whitespace))
+        }) else {
+            return (&line[..indent_len], &line[..indent_len]);
+        };
+
+        let key_len = indent_len.saturating_add(marker.len());
+        let after = &line[key_len..];
+        let spaces = after.len() - after.trim_start_matches([' ', '\t']).len();
+        (&line[..key_len], &line[..key_len.saturating_add(spaces)])
+    }
+
+    /// 行去掉前缀后没有内容
+    fn is_blank(line: &str) -> bool {
+        line[Self::prefix(line).1.len()..].trim().is_empty()
+    }
+
+    /// 列表项的标记及其后的空白，content 是去掉前缀后的行内容；不是列表项时返回 None
+    fn list_marker(content: &str) -> Option<&str> {
+        let digits = content.len()
+            - content
+                .trim_start_matches(|char: char| char.is_ascii_digit())
+                .len();
+        let marker_len = if digits > 0 {
+            content[digits..]
+                .starts_with(['.', ')'])
+                .then_some(digits.saturating_add(1))?
+        } else {
+            content.starts_with(['-', '*', '+']).then_some(1)?
+        };
+
+        let rest = &content[marker_len..];
+        let spaces = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        (spaces > 0 && !rest.trim().is_empty())
+            .then(|| &content[..marker_len.saturating_add(spaces)])
+    }
+
+    /// 重排后各行的前缀
+    /// 返回：(首行的前缀，列表项包含列表标记, 后续行的前缀，列表项的后续行缩进到列表项的文本处)
+    fn fill_prefixes(line: &str) -> (&str, String) {
+        let prefix = Self::prefix(line).1;
+        match Self::list_marker(&line[prefix.len()..]) {
+            Some(marker) => (
+                &line[..prefix.len().saturating_add(marker.len())],
+                format!("{prefix}{}", " ".repeat(marker.chars().count())),
+            ),
+            None => (prefix, prefix.to_string()),
+        }
+    }
+
+    fn is_list_item(line: &str) -> bool {
+        Self::list_marker(&line[Self::prefix(line).1.len()..]).is_some()
+    }
+
+    /// 以 first 开头的段落中，line 能否作为后续行
+    /// 列表项的后续行的前缀必须正好缩进到列表项的文本处，其他段落的后续行与首行的缩进和注释符号相同
+    fn continues(first: &str, line: &str) -> bool {
+        if Self::is_blank(line) || Self::is_list_item(line) {
+            return false;
+        }
+
+        if Self::is_list_item(first) {
+            Self::prefix(line).1 == Self::fill_prefixes(first).1
+        } else {
+            Self::prefix(line).0 == Self::prefix(first).0
+        }
+    }
+
+    /// 光标所在的段落，光标在空行上时返回 None
+    fn paragraph(edit_area: &EditArea) -> Option<(LineIdx, LineIdx)> {
+        let line_idx = edit_area.caret().line_idx;
+        let text_of = |line_idx: LineIdx| TextLine::lines_text(edit_area, line_idx, line_idx);
+        if Self::is_blank(&text_of(line_idx)) {
+            return None;
+        }
+
+        // 向上找到段落的首行：列表项本身就是首行，否则并入属于同一段落的上一行
+        let mut first = line_idx;
+        while first > 0 && !Self::is_list_item(&text_of(first)) {
+            let prev = text_of(first.saturating_sub(1));
+            if !Self::continues(&prev, &text_of(first)) {
+                break;
+            }
+            first = first.saturating_sub(1);
+        }
+
+        let first_text = text_of(first);
+        let mut last = line_idx;
+        while last.saturating_add(1) < edit_area.lines_len()
+            && Self::continues(&first_text, &text_of(last.saturating_add(1)))
+        {
+            last = last.saturating_add(1);
+        }
+
+        Some((first, last))
+    }
+
+    /// 重排多行文本：逐段重排，空行和段落之间的分隔保持原样
+    fn reflow(lines: &[String], width: usize, tab_width: usize) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut idx = 0;
+
+        while idx < lines.len() {
+            if Self::is_blank(&lines[idx]) {
+                result.push(lines[idx].clone());
+                idx = idx.saturating_add(1);
+                continue;
+            }
+
+            let first = &lines[idx];
+            let len = lines[idx.saturating_add(1)..]
+                .iter()
+                .position(|line| !Self::continues(first, line))
+                .unwrap_or(lines.len().saturating_sub(idx).saturating_sub(1))
+                .saturating_add(1);
+            let (first_prefix, prefix) = Self::fill_prefixes(first);
+            let contents: Vec<&str> = iter::once(&first[first_prefix.len()..])
+                .chain(
+                    lines[idx.saturating_add(1)..idx.saturating_add(len)]
+                        .iter()
+                        .map(|line| &line[Self::prefix(line).1.len()..]),
+                )
+                .collect();
+
+            result.extend(Self::fill(
+                (first_prefix, &prefix),
+                &Self::atoms(&contents, tab_width),
+                width,
+                tab_width,
+            ));
+            idx = idx.saturating_add(len);
+        }
+
+        result
+    }
+
+    /// 把段落各行的内容拆分为折行单位
+    /// 行与行相接处，两边都是宽字素时直接相连，否则以一个空格分隔
+    fn atoms(contents: &[&str], tab_width: usize) -> Vec<Atom> {
+        let mut atoms: Vec<Atom> = Vec::new();
+
+        for content in contents {
+            for (word_idx, word) in content.split_whitespace().enumerate() {
+                let word_start = atoms.len();
+
+                for cell in Cell::str_to_cells(word, tab_width) {
+                    let grapheme = cell.to_grapheme();
+                    let is_wide = cell.cell_width() > 1;
+                    let is_in_word = atoms.len() > word_start;
+
+                    if let Some(last) = atoms.last_mut()
+                        && is_in_word
+                        && (CLOSING_PUNCTUATION.contains(grapheme) || !(is_wide || last.is_wide))
+                    {
+                        last.text.push_str(grapheme);
+                        last.width = last.width.saturating_add(cell.cell_width());
+                        continue;
+                    }
+
+                    let space_before = if is_in_word {
+                        false
+                    } else if word_idx > 0 {
+                        true
+                    } else {
+                        atoms.last().is_some_and(|last| !(last.is_wide && is_wide))
+                    };
+                    atoms.push(Atom {
+                        text: grapheme.to_string(),
+                        width: cell.cell_width(),
+                        is_wide,
+                        space_before,
+                    });
+                }
+            }
+        }
+
+        atoms
+    }
+
+    /// 贪心地把折行单位放入宽度不超过 width 的行中，首行以 first_prefix 开头，其余行以 prefix 开头；
+    /// 单个超宽的单位独占一行
+    fn fill(
+        (first_prefix, prefix): (&str, &str),
+        atoms: &[Atom],
+        width: usize,
+        tab_width: usize,
+    ) -> Vec<String> {
+        let prefix_width = Self::width(prefix, tab_width);
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        let mut line_width = Self::width(first_prefix, tab_width);
+
+        for atom in atoms {
+            if !line.is_empty() {
+                let space = usize::from(atom.space_before);
+                if line_width.saturating_add(space).saturating_add(atom.width) > width {
+                    let line_prefix = if lines.is_empty() {
+                        first_prefix
+                    } else {
+                        prefix
+                    };
+                    lines.push(format!("{line_prefix}{line}"));
+                    line.clear();
+                    line_width = prefix_width;
+                } else if atom.space_before {
+                    line.push(' ');
+                    line_width = line_width.saturating_add(1);
+                }
+            }
+
+            line.push_str(&atom.text);
+            line_width = line_width.saturating_add(atom.width);
+        }
+        if !line.is_empty() {
+            let line_prefix = if lines.is_empty() {
+                first_prefix
+            } else {
+                prefix
+            };
+            lines.push(format!("{line_prefix}{line}"));
+        }
+
+        lines
+    }
+
+    /// 文本占据的终端列宽
+    fn width(text: &str, tab_width: usize) -> usize {
+        Cell::str_to_cells(text, tab_width)
+            .iter()
+            .map(Cell::cell_width)
+            .sum()
+    }
+
+    /// 自动折行时的折行位置：光标之前、宽度不超过 width 的最后一处空白，或两个宽字素之间
+    /// 返回：要替换为换行的图元范围 [start, end)，找不到折行位置时返回 None
+    fn fill_break(
+        line: &str,
+        caret: CellIdx,
+        width: usize,
+        tab_width: usize,
+    ) -> Option<(CellIdx, CellIdx)> {
+        let cells = Cell::str_to_cells(line, tab_width);
+        let prefix = Self::fill_prefixes(line).0;
+        let prefix_cells = Cell::str_to_cells(prefix, tab_width).len();
+        let is_space = |cell: &Cell| matches!(cell.to_grapheme(), " " | "\t");
+
+        let mut result = None;
+        let mut col = Self::width(prefix, tab_width);
+        let mut cell_idx = prefix_cells;
+
+        while cell_idx < caret.min(cells.len()) {
+            let cell = &cells[cell_idx];
+            if col > width {
+                break;
+            }
+
+            if is_space(cell) {
+                let end = cells[cell_idx..]
+                    .iter()
+                    .position(|cell| !is_space(cell))
+                    .map_or(cells.len(), |len| cell_idx.saturating_add(len));
+                if cell_idx > prefix_cells && end < caret {
+                    result = Some((cell_idx, end));
+                }
+                let spaces_width: usize = cells[cell_idx..end].iter().map(Cell::cell_width).sum();
+                col = col.saturating_add(spaces_width);
+                cell_idx = end;
+                continue;
+            }
+
+            if let Some(prev) = cell_idx.checked_sub(1).map(|idx| &cells[idx])
+                && cell_idx > prefix_cells
+                && !is_space(prev)
+                && (prev.cell_width() > 1 || cell.cell_width() > 1)
+                && !CLOSING_PUNCTUATION.contains(cell.to_grapheme())
+            {
+                result = Some((cell_idx, cell_idx));
+            }
+            col = col.saturating_add(cell.cell_width());
+            cell_idx = cell_idx.saturating_add(1);
+        }
+
+        result
+    }
+
+    /// 输入后光标所在行超出折行宽度时，在折行位置换行，新行沿用当前行的缩进和注释符号，
+    /// 在列表项中折行时新行缩进到列表项的文本处
+    pub fn auto_fill(edit_area: &mut EditArea) {
+        let position = edit_area.caret().clone();
+        let line_idx = position.line_idx;
+        let width = edit_area.wrap_width();
+        if edit_area.line_cell_width_until(line_idx, position.cell_idx) <= width {
+            return;
+        }
+
+        let text = TextLine::lines_text(edit_area, line_idx, line_idx);
+        let tab_width = edit_area.document().tab_width();
+        let Some((start, end)) = Self::fill_break(&text, position.cell_idx, width, tab_width)
+        else {
+            return;
+        };
+
+        let start = DocumentCoordinate {
+            line_idx,
+            cell_idx: start,
+        };
+        let end = DocumentCoordinate {
+            line_idx,
+            cell_idx: end,
+        };
+        let line_ending = edit_area.document().line_ending();
+        let break_text = format!("{}{}", line_ending.as_str(), Self::fill_prefixes(&text).1);
+
+        let mut caret = Caret {
+            position,
+            anchor: None,
+        };
+        let text_end = edit_area.replace_text(start.clone(), end.clone(), &break_text);
+        caret.shift(&start, &end, &text_end);
+        TextCaretMove::move_caret_validly(edit_area, caret.position);
+    }
+
+    /// 重排段落，作为一次编辑记录；有选区时选中重排后的行，否则光标停在段落末尾
+    fn reflow_paragraph(edit_area: &mut EditArea) {
+        let range = if edit_area.selection().is_some() {
+            Some(TextLine::line_range(edit_area))
+        } else {
+            Self::paragraph(edit_area)
+        };
+        let Some((first, last)) = range else {
+            return;
+        };
+
+        let lines = TextLine::lines(edit_area, first, last);
+        let reflowed = Self::reflow(
+            &lines,
+            edit_area.wrap_width(),
+            edit_area.document().tab_width(),
+        );
+        if reflowed == lines {
+            return;
+        }
+
+        let had_selection = edit_area.selection().is_some();
+        edit_area.replace_lines(first, last, &reflowed);
+        if !had_selection {
+            let line_idx = first.saturating_add(reflowed.len()).saturating_sub(1);
+            let position = DocumentCoordinate {
+                line_idx,
+                cell_idx: edit_area.line_cell_count(line_idx),
+            };
+            TextCaretMove::move_caret_validly(edit_area, position);
+        }
+    }
+}
+
+impl TryFrom<KeyEvent> for TextReflow {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        match (code, modifiers) {
+            (KeyCode::Char('p'), KeyModifiers::ALT) => Ok(Self::Paragraph),
+            (KeyCode::Char('a'), KeyModifiers::ALT) => Ok(Self::ToggleAutoFill),
+            _ => Err(format!("重排命令不支持：{modifiers:?} + {code:?}")),
+        }
+    }
+}
+
+impl TryExecute for TextReflow {
+    fn execute(self, editor: &mut Editor) {
+        match self {
+            Self::Paragraph => {
+                let edit_area = editor.mut_edit_area();
+                Self::reflow_paragraph(edit_area);
+                TextScroll::scroll_text(edit_area);
+                editor.update_status();
+            }
+            Self::ToggleAutoFill => {
+                let edit_area = editor.mut_edit_area();
+                let is_auto_fill = edit_area.toggle_auto_fill();
+                let width = edit_area.wrap_width();
+                editor
+                    .mut_cmd_line()
+                    .set_prompt_for_auto_fill(is_auto_fill, width);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reflow(lines: &[&str], width: usize) -> Vec<String> {
+        let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        TextReflow::reflow(&lines, width, Cell::DEFAULT_TAB_WIDTH)
+    }
+
+    #[test]
+    fn reflow_keeps_indent_and_comment_prefix() {
+        assert_eq!(
+            reflow(&["    // one two", "    // three four five"], 18),
+            ["    // one two", "    // three four", "    // five"]
+        );
+        assert_eq!(
+            reflow(&["/// a", "/// b", "", "c"], 80),
+            ["/// a b", "", "c"]
+        );
+    }
+
+    #[test]
+    fn reflow_separates_different_prefixes() {
+        assert_eq!(reflow(&["# a", "b"], 80), ["# a", "b"]);
+    }
+
+    #[test]
+    fn reflow_joins_wide_graphemes_without_spaces() {
+        assert_eq!(reflow(&["中文", "段落"], 80), ["中文段落"]);
+        assert_eq!(reflow(&["中文", "text"], 80), ["中文 text"]);
+        assert_eq!(reflow(&["中文段落"], 4), ["中文", "段落"]);
+    }
+
+    #[test]
+    fn reflow_keeps_closing_punctuation_on_the_line() {
+        assert_eq!(reflow(&["中文，段落。"], 6), ["中文，", "段落。"]);
+        assert_eq!(reflow(&["aaa bbb, ccc."], 7), ["aaa", "bbb,", "ccc."]);
+    }
+
+    #[test]
+    fn reflow_starts_paragraph_at_list_items() {
+        assert_eq!(
+            reflow(&["- a", "- b", "1. c", "   d"], 80),
+            ["- a", "- b", "1. c d"]
+        );
+        assert_eq!(
+            reflow(&["* one two three", "  four"], 10),
+            ["* one two", "  three", "  four"]
+        );
+        assert_eq!(reflow(&["// - a", "//   b"], 80), ["// - a b"]);
+    }
+
+    #[test]
+    fn list_marker_requires_text_after_space() {
+        assert_eq!(TextReflow::list_marker("- a"), Some("- "));
+        assert_eq!(TextReflow::list_marker("12) a"), Some("12) "));
+        assert_eq!(TextReflow::list_marker("-a"), None);
+        assert_eq!(TextReflow::list_marker("- "), None);
+        assert_eq!(TextReflow::list_marker("3.14 is pi"), None);
+    }
+
+    #[test]
+    fn fill_break_at_last_space_within_width() {
+        assert_eq!(
+            TextReflow::fill_break("aaa bbb ccc", 11, 8, 4),
+            Some((7, 8))
+        );
+        assert_eq!(TextReflow::fill_break("中文段落", 4, 4, 4), Some((2, 2)));
+        assert_eq!(TextReflow::fill_break("- aaaaaaaa", 10, 4, 4), None);
+    }
+}
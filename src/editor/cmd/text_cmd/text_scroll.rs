@@ -25,7 +25,7 @@ impl TextScroll {
         let end = offset.row.saturating_add(height).saturating_sub(1);
 
         // 判断目标行是否超出当前可视区域，决定是否更新偏移量
-        let offset_changed = if caret_line < start {
+        if caret_line < start {
             // 情况1：目标行在当前编辑区域第一行的**上方**，则可视区域上移到目标行
             offset.row = caret_line;
             true
@@ -38,9 +38,7 @@ impl TextScroll {
         } else {
             // 情况3：目标行在当前可视区域内（无需调整偏移量）
             false
-        };
-
-        offset_changed
+        }
     }
 
     /// 横向滚动文本，滚动到指定列
@@ -58,7 +56,7 @@ impl TextScroll {
         let end = offset.col.saturating_add(width).saturating_sub(1);
 
         // 判断目标行是否超出当前可视区域，决定是否更新偏移量
-        let offset_changed = if caret_col < start {
+        if caret_col < start {
             // 情况1：目标行在当前可视区域第一行的**上方**，则可视区域上移到目标行
             offset.col = caret_col;
             true
@@ -70,8 +68,6 @@ impl TextScroll {
         } else {
             // 情况3：目标行在当前可视区域内（无需调整偏移量）
             false
-        };
-
-        offset_changed
+        }
     }
 }
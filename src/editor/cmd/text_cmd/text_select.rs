@@ -0,0 +1,43 @@
+use crossterm::event::{KeyEvent, KeyModifiers};
+
+use crate::{
+    Editor,
+    editor::cmd::{TryExecute, text_cmd::TextCaretMove},
+};
+
+/// SHIFT + 光标移动键（含 CTRL + SHIFT + 左右键按单词移动）：以开始选择时的光标位置为锚点，随光标移动扩展选区
+pub struct TextSelect {
+    caret_move: TextCaretMove,
+}
+
+impl TryFrom<KeyEvent> for TextSelect {
+    type Error = String;
+
+    fn try_from(event: KeyEvent) -> Result<Self, Self::Error> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = event;
+
+        if modifiers == KeyModifiers::SHIFT {
+            let caret_move = TextCaretMove::from_code(code)?;
+            Ok(Self { caret_move })
+        } else if modifiers == KeyModifiers::SHIFT | KeyModifiers::CONTROL {
+            let caret_move = TextCaretMove::from_word_code(code)?;
+            Ok(Self { caret_move })
+        } else {
+            Err(format!("选择文本不支持：{modifiers:?} + {code:?}"))
+        }
+    }
+}
+
+impl TryExecute for TextSelect {
+    fn execute(self, editor: &mut Editor) {
+        editor.mut_edit_area().for_each_caret(|edit_area| {
+            edit_area.start_selection();
+            self.caret_move.move_caret(edit_area);
+        });
+
+        // 更新状态栏
+        editor.update_status();
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    fmt::{Display, Formatter, Result},
+    io,
+};
+
+use crate::{
+    editor::ui::ReadOnly,
+    file::{Encoding, FileType},
+};
+
+/// 编辑器操作中的错误，显示在命令行中，不会中断编辑器的运行
+pub enum EditorError {
+    // 读取文件失败
+    ReadFile(io::Error),
+    // 写入文件失败
+    SaveFile(io::Error),
+    // 文本中有保存编码无法表示的字符
+    Unencodable(Encoding),
+    // 只读文档不允许保存
+    SaveReadOnly(ReadOnly),
+    // 只读的原因不允许解除只读
+    LockedReadOnly(ReadOnly),
+    // 读取终端事件失败
+    ReadEvent(io::Error),
+    // 寄存器名不合法
+    InvalidRegister(String),
+    // 寄存器中没有可粘贴的内容
+    EmptyRegister(Option<char>),
+    // 大小写转换的风格按键不合法
+    InvalidCase(String),
+    // 行命令不合法
+    InvalidLineCmd(String),
+    // 缩进设置不合法
+    InvalidIndent(String),
+    // 文件类型没有行注释
+    NoLineComment(FileType),
+    // 文件类型没有块注释
+    NoBlockComment(FileType),
+    // 宏命令不合法
+    InvalidMacro(String),
+    // 宏为空，None 表示最近一次录制的宏
+    EmptyMacro(Option<char>),
+    // 录制宏或回放宏时不能回放宏
+    NestedMacro,
+    // 保存宏失败
+    SaveMacro(io::Error),
+    // 读取交换文件失败
+    ReadSwap(io::Error),
+    // 写入交换文件失败
+    WriteSwap(io::Error),
+}
+
+impl Display for EditorError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::ReadFile(err) => write!(formatter, "读取文件失败，已只读打开：{err}"),
+            Self::SaveFile(err) => write!(formatter, "保存失败，文件未被修改：{err}"),
+            Self::Unencodable(encoding) => {
+                write!(formatter, "保存失败：文本中有 {encoding} 无法表示的字符")
+            }
+            Self::SaveReadOnly(read_only) if read_only.can_unlock() => {
+                write!(formatter, "只读文档无法保存，ALT + R 解除只读")
+            }
+            Self::SaveReadOnly(read_only) | Self::LockedReadOnly(read_only) => match read_only {
+                ReadOnly::ReadError => {
+                    write!(
+                        formatter,
+                        "文件读取失败，不能解除只读，以免空文档覆盖原文件"
+                    )
+                }
+                ReadOnly::SwapBusy => {
+                    write!(formatter, "另一个进程正在编辑此文件，不能解除只读")
+                }
+                ReadOnly::Manual | ReadOnly::Binary => write!(formatter, "只读文档无法保存"),
+            },
+            Self::ReadEvent(err) => write!(formatter, "读取终端事件失败：{err}"),
+            Self::InvalidRegister(name) => {
+                write!(formatter, "寄存器名必须是单个小写字母或数字：{name}")
+            }
+            Self::EmptyRegister(Some(name)) => write!(formatter, "寄存器 {name} 为空"),
+            Self::EmptyRegister(None) => write!(formatter, "没有可粘贴的内容"),
+            Self::InvalidCase(key) => write!(formatter, "不支持的转换风格：{key}"),
+            Self::InvalidLineCmd(input) => write!(formatter, "不支持的行命令：{input}"),
+            Self::InvalidIndent(input) => write!(formatter, "不支持的缩进设置：{input}"),
+            Self::NoLineComment(file_type) => write!(formatter, "{file_type} 文件没有行注释"),
+            Self::NoBlockComment(file_type) => write!(formatter, "{file_type} 文件没有块注释"),
+            Self::InvalidMacro(input) => write!(formatter, "不支持的宏命令：{input}"),
+            Self::EmptyMacro(Some(name)) => write!(formatter, "宏 {name} 为空"),
+            Self::EmptyMacro(None) => write!(formatter, "还没有录制宏，ALT + Q 开始录制"),
+            Self::NestedMacro => write!(formatter, "录制或回放宏时不能回放宏"),
+            Self::SaveMacro(err) => write!(formatter, "保存宏失败：{err}"),
+            Self::ReadSwap(err) => write!(formatter, "读取交换文件失败，未恢复：{err}"),
+            Self::WriteSwap(err) => {
+                write!(formatter, "写入交换文件失败，异常退出后将无法恢复：{err}")
+            }
+        }
+    }
+}
@@ -1,24 +1,57 @@
 use crossterm::event::{self, Event};
+use std::{
+    io, thread,
+    time::{Duration, Instant},
+};
 
 mod base;
 mod cmd;
+mod error;
 mod ui;
-use base::Cell;
-use base::Line;
-use cmd::Cmd;
-use ui::{CmdLine, EditArea, StatusBar, UI};
+use base::{
+    Caret, Case, Cell, Change, Document, History, Line, MacroInput, Macros, Pasted, Reencoding,
+    Registers, Yank,
+};
+use cmd::{Cmd, Recover, Save, TryExecute};
+pub use error::EditorError;
+use ui::{CmdLine, EditArea, ReadOnly, StatusBar, UI};
 
 use crate::editor::cmd::DelayCmd;
-use crate::{Terminal, file::FileInfo, prelude::Size};
+use crate::{
+    Terminal,
+    config::Config,
+    file::{FileInfo, SwapFile, SwapState},
+    prelude::{LineIdx, Size},
+};
+
+// 读取终端事件失败后，再次读取前的等待时间
+const EVENT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+// 用户停止输入超过此时间后，把未保存的修改写入交换文件
+const SWAP_IDLE_TIME: Duration = Duration::from_secs(2);
 
 /// 编辑器
+#[derive(Default)]
 pub struct Editor {
     // 是否退出编辑器
     is_quit: bool,
+    // 编辑器配置
+    config: Config,
     // 编辑器当前的延时命令, (当前延时命令, 是否执行延时命令)
     delay_cmd: Option<(DelayCmd, bool)>,
     // 文件信息
     file_info: FileInfo,
+    // 剪切和复制用的寄存器
+    registers: Registers,
+    // 键盘宏
+    macros: Macros,
+    // 交换文件，用于异常退出后恢复未保存的修改
+    swap_file: SwapFile,
+    // 最近一次输入的时间，用于计算空闲时间
+    last_input_at: Option<Instant>,
+    // 最近一次编辑后的文档版本，以及编辑时光标所在的行
+    edited: (usize, Option<LineIdx>),
+    // 上次尝试自动保存时的文档版本，保存失败后，文档再次修改前不会重试
+    autosaved_version: Option<usize>,
     // 终端
     terminal: Terminal,
     // 编辑区
@@ -31,21 +64,49 @@ pub struct Editor {
 
 impl Editor {
     pub fn new(file_path: &str) -> Self {
-        let mut editor = Editor::default();
+        // 读取配置，初始化文件信息
+        let mut editor = Editor {
+            config: Config::load(),
+            file_info: FileInfo::from(file_path),
+            macros: Macros::load(),
+            ..Default::default()
+        };
 
-        // 初始化文件信息
-        editor.file_info = FileInfo::from(file_path);
         // 初始化终端标题
         editor.terminal.set_title(editor.file_info.get_name());
-        // 初始化编辑区文档
-        editor.edit_area.load(&editor.file_info.get_path_str());
+        // 初始化编辑区文档，二进制文件或读取失败的文件以只读方式打开，并在命令行中警告
+        match editor.edit_area.load(&editor.file_info.get_path_str()) {
+            Ok(Some(binary_kind)) => editor.cmd_line.set_prompt_for_binary(&binary_kind),
+            Ok(None) => {}
+            Err(err) => editor.show_error(err),
+        }
+        editor
+            .edit_area
+            .set_file_type(editor.file_info.get_file_type().clone());
+        editor
+            .edit_area
+            .set_indent_style(editor.config.tab_width, editor.config.soft_tabs);
+        editor
+            .edit_area
+            .set_wrap(editor.config.wrap_width, editor.config.auto_fill);
+        // 检查交换文件：另一个进程正在编辑时只读打开，有遗留的修改时询问是否恢复
+        let swap_state;
+        (editor.swap_file, swap_state) = SwapFile::open(editor.file_info.get_path());
+        match swap_state {
+            SwapState::Busy(pid) => {
+                // 读取失败的文档保持原来的只读原因，两者都不能解除只读
+                if editor.edit_area.read_only() != Some(ReadOnly::ReadError) {
+                    editor.edit_area.set_read_only(Some(ReadOnly::SwapBusy));
+                }
+                editor.cmd_line.set_prompt_for_swap_busy(pid);
+            }
+            SwapState::Orphaned if !editor.edit_area.is_read_only() => {
+                Recover::Enable.execute(&mut editor)
+            }
+            SwapState::Orphaned | SwapState::Missing => {}
+        }
         // 初始化状态栏
-        editor.status_bar.update_status(
-            editor.file_info.clone(),
-            editor.edit_area.lines_len(),
-            editor.edit_area.is_modified(),
-            editor.edit_area.caret().clone(),
-        );
+        editor.update_status();
 
         // 调整组件尺寸，
         editor.resize_all();
@@ -66,17 +127,18 @@ impl Editor {
             // 每当匹配一个命令后，都会循环到此处，触发刷新屏幕函数
             self.refresh_screen();
 
-            match event::read() {
-                Ok(event) => self.evaluate_event(event),
+            match self.next_event() {
+                Ok(Some(event)) => {
+                    self.last_input_at = Some(Instant::now());
+                    self.evaluate_event(event);
+                    self.after_event();
+                }
+                // 用户停止输入一段时间
+                Ok(None) => self.on_idle(),
                 Err(err) => {
-                    #[cfg(debug_assertions)]
-                    {
-                        panic!("Could not read event: {err:?}");
-                    }
-                    #[cfg(not(debug_assertions))]
-                    {
-                        let _ = err;
-                    }
+                    self.show_error(EditorError::ReadEvent(err));
+                    // 终端持续出错时，避免空转占满 CPU
+                    thread::sleep(EVENT_RETRY_INTERVAL);
                 }
             }
         }
@@ -115,11 +177,122 @@ impl Editor {
             return;
         }
 
-        if let Event::Key(key_event) = event {
-            Cmd::handler(key_event, self);
+        match event {
+            Event::Key(key_event) => Cmd::handler(key_event, self),
+            Event::Paste(text) => Cmd::paste_handler(&text, self),
+            _ => {}
+        }
+    }
+
+    /// 等待下一个终端事件，有待办的空闲任务时，到期仍无输入则返回 None
+    fn next_event(&self) -> io::Result<Option<Event>> {
+        match self.idle_timeout() {
+            Some(timeout) => {
+                event::poll(timeout).and_then(|is_ready| is_ready.then(event::read).transpose())
+            }
+            None => event::read().map(Some),
+        }
+    }
+
+    /// 距最早的空闲任务到期还有多久，没有待办的空闲任务时返回 None
+    fn idle_timeout(&self) -> Option<Duration> {
+        let version = self.edit_area.document().version();
+
+        let swap_idle = (self.edit_area.is_modified() && self.swap_file.is_outdated(version))
+            .then_some(SWAP_IDLE_TIME);
+        let autosave_idle = (self.config.autosave_idle > 0 && self.can_autosave())
+            .then(|| Duration::from_secs(self.config.autosave_idle));
+
+        let idle_time = swap_idle.into_iter().chain(autosave_idle).min()?;
+        let elapsed = self.last_input_at.map_or(idle_time, |at| at.elapsed());
+
+        Some(idle_time.saturating_sub(elapsed))
+    }
+
+    /// 处理完一个事件后的收尾工作
+    fn after_event(&mut self) {
+        // 持续输入时也要定期写入交换文件；修改被撤销完时及时删除交换文件
+        if self.swap_file.is_overdue() || !self.edit_area.is_modified() {
+            self.update_swap_file();
+        }
+
+        if self.config.autosave_on_leave {
+            self.autosave_on_leave();
+        }
+    }
+
+    /// 空闲任务到期
+    fn on_idle(&mut self) {
+        let idle = self.last_input_at.map_or(Duration::MAX, |at| at.elapsed());
+
+        if idle >= SWAP_IDLE_TIME {
+            self.update_swap_file();
+        }
+
+        if self.config.autosave_idle > 0 && idle >= Duration::from_secs(self.config.autosave_idle) {
+            self.autosave();
+        }
+    }
+
+    /// 文档被编辑后，光标离开所编辑的行时自动保存
+    fn autosave_on_leave(&mut self) {
+        let version = self.edit_area.document().version();
+        let line_idx = self.edit_area.caret().line_idx;
+
+        match self.edited {
+            // 本次事件修改了文档，记下光标所在的行
+            (edited_version, _) if edited_version != version => {
+                self.edited = (version, Some(line_idx));
+            }
+            (_, Some(edited_line_idx)) if edited_line_idx != line_idx => {
+                self.edited.1 = None;
+                self.autosave();
+            }
+            _ => {}
+        }
+    }
+
+    /// 自动保存，与 CTRL + S 共用保存流程
+    fn autosave(&mut self) {
+        if !self.can_autosave() {
+            return;
+        }
+
+        self.autosaved_version = Some(self.edit_area.document().version());
+        Save {}.execute(self);
+    }
+
+    /// 文档有未保存的修改且可以保存；编辑命令时不自动保存，以免覆盖命令行
+    fn can_autosave(&self) -> bool {
+        self.edit_area.is_modified()
+            && !self.edit_area.is_read_only()
+            && self.delay_cmd.is_none()
+            && self.autosaved_version != Some(self.edit_area.document().version())
+    }
+
+    /// 同步交换文件：文档有未保存的修改时写入交换文件，没有时删除交换文件
+    fn update_swap_file(&mut self) {
+        if !self.edit_area.is_modified() {
+            self.swap_file.remove();
+            return;
+        }
+
+        let document = self.edit_area.document();
+        let version = document.version();
+        if !self.swap_file.is_outdated(version) {
+            return;
+        }
+
+        if let Err(err) = self.swap_file.write(&document.to_text(), version) {
+            self.show_error(EditorError::WriteSwap(err));
         }
     }
 
+    /// 在命令行中显示错误消息
+    pub fn show_error(&mut self, error: EditorError) {
+        self.cmd_line.set_error(&error);
+    }
+
     pub fn set_is_quit(&mut self, is_quit: bool) {
         self.is_quit = is_quit;
     }
@@ -151,12 +324,20 @@ impl Editor {
     pub fn update_status(&mut self) {
         let file_info = self.file_info.clone();
         let edit_area = self.edit_area();
+        let file_format = edit_area.document().format();
         let total_lens = edit_area.lines_len();
         let is_modified = edit_area.is_modified();
+        let is_read_only = edit_area.is_read_only();
         let caret = edit_area.caret().clone();
 
-        self.status_bar
-            .update_status(file_info, total_lens, is_modified, caret);
+        self.status_bar.update_status(
+            file_info,
+            file_format,
+            total_lens,
+            is_modified,
+            is_read_only,
+            caret,
+        );
     }
 
     pub fn mut_cmd_line(&mut self) -> &mut CmdLine {
@@ -216,17 +397,3 @@ impl Editor {
         self.cmd_line.draw(Terminal::size().height);
     }
 }
-
-impl Default for Editor {
-    fn default() -> Self {
-        Self {
-            is_quit: false,
-            delay_cmd: Option::None,
-            terminal: Terminal::default(),
-            edit_area: EditArea::default(),
-            file_info: FileInfo::default(),
-            status_bar: StatusBar::default(),
-            cmd_line: CmdLine::default(),
-        }
-    }
-}
@@ -1,17 +1,26 @@
 use crate::{
     Terminal,
-    editor::{Line, UI},
+    editor::{Case, EditorError, Line, UI, Yank},
+    file::{BinaryKind, Encoding, LineEnding},
     prelude::{DocumentCoordinate, Size, TerminalCoordinate},
 };
 
 const SAVE_PROMPT: &str = "保存文件!";
 const FIND_PROMPT: &str = "查找：";
+const REGISTER_PROMPT: &str = "寄存器：";
+const LINES_PROMPT: &str = "行命令（sort [n|i|v]、uniq、reverse、shuffle、keep 模式、drop 模式）：";
+const MACRO_PROMPT: &str =
+    "宏（[次数] 回放最近录制的宏，名称[次数] 回放命名宏，>名称 保存为命名宏）：";
+const CASE_PROMPT: &str =
+    "转换为（u 大写 l 小写 t 首字母大写 s snake c camel p Pascal k kebab S SCREAMING）：";
 
 // 命令行，用于输入命令，显示提示信息
 pub struct CmdLine {
     size: Size,
     // 命令的提示消息
     prompt_msg: Line,
+    // 提示消息是否为错误消息，错误消息以醒目的颜色显示
+    is_error: bool,
     // 命令输入区域
     input: Line,
     // 光标在命令行的位置，虽然是文档位置，但这是为了方便计算光标在图元间的移动和转为终端位置
@@ -35,9 +44,165 @@ impl CmdLine {
         self.set_prompt_msg(FIND_PROMPT);
     }
 
+    /// 设置选择寄存器时的提示消息
+    pub fn set_prompt_for_register(&mut self) {
+        self.set_prompt_msg(REGISTER_PROMPT);
+    }
+
+    /// 设置选定寄存器后的提示消息
+    pub fn set_prompt_for_register_selected(&mut self, name: char) {
+        self.set_prompt_msg(&format!("下一次剪切、复制或粘贴使用寄存器 {name}"));
+    }
+
+    /// 设置大小写转换命令的提示消息
+    pub fn set_prompt_for_case(&mut self) {
+        self.set_prompt_msg(CASE_PROMPT);
+    }
+
+    /// 设置大小写转换后的提示消息
+    pub fn set_prompt_for_case_converted(&mut self, case: Case) {
+        self.set_prompt_msg(&format!("已转换为 {case}"));
+    }
+
+    /// 设置缩进设置命令的提示消息，显示当前的缩进风格
+    pub fn set_prompt_for_indent(&mut self, tab_width: usize, soft_tabs: bool) {
+        self.set_prompt_msg(&format!(
+            "缩进（当前 {}，s 空格 t 制表符，后跟宽度，如 s4）：",
+            Self::indent_style(tab_width, soft_tabs)
+        ));
+    }
+
+    /// 设置缩进风格修改后的提示消息
+    pub fn set_prompt_for_indent_set(&mut self, tab_width: usize, soft_tabs: bool) {
+        self.set_prompt_msg(&format!(
+            "本文档的缩进已改为 {}",
+            Self::indent_style(tab_width, soft_tabs)
+        ));
+    }
+
+    fn indent_style(tab_width: usize, soft_tabs: bool) -> String {
+        if soft_tabs {
+            format!("{tab_width} 个空格")
+        } else {
+            format!("制表符（宽度 {tab_width}）")
+        }
+    }
+
+    /// 设置行命令的提示消息
+    pub fn set_prompt_for_process_lines(&mut self) {
+        self.set_prompt_msg(LINES_PROMPT);
+    }
+
+    /// 设置行命令执行后的提示消息
+    pub fn set_prompt_for_lines_processed(&mut self, count: usize, processed: usize) {
+        self.set_prompt_msg(&format!("已处理 {count} 行，剩余 {processed} 行"));
+    }
+
+    /// 设置开始录制宏时的提示消息
+    pub fn set_prompt_for_recording(&mut self) {
+        self.set_prompt_msg("正在录制宏，ALT + Q 结束录制");
+    }
+
+    /// 设置结束录制宏时的提示消息
+    pub fn set_prompt_for_recorded(&mut self, count: usize) {
+        self.set_prompt_msg(&format!("已录制宏（{count} 次输入），CTRL + P 回放"));
+    }
+
+    /// 设置宏命令的提示消息
+    pub fn set_prompt_for_macro(&mut self) {
+        self.set_prompt_msg(MACRO_PROMPT);
+    }
+
+    /// 设置回放宏后的提示消息
+    pub fn set_prompt_for_macro_replayed(&mut self, times: usize) {
+        self.set_prompt_msg(&format!("已回放宏 {times} 次"));
+    }
+
+    /// 设置保存命名宏后的提示消息
+    pub fn set_prompt_for_macro_saved(&mut self, name: char) {
+        self.set_prompt_msg(&format!("已保存宏 {name}"));
+    }
+
+    /// 设置切换自动折行后的提示消息
+    pub fn set_prompt_for_auto_fill(&mut self, is_auto_fill: bool, width: usize) {
+        if is_auto_fill {
+            self.set_prompt_msg(&format!("已开启自动折行（{width} 列）"));
+        } else {
+            self.set_prompt_msg("已关闭自动折行");
+        }
+    }
+
+    /// 设置剪切或复制后的提示消息
+    pub fn set_prompt_for_yank(&mut self, is_cut: bool, yank: &Yank, name: Option<char>) {
+        let action = if is_cut { "剪切" } else { "复制" };
+        let target = if yank.is_line {
+            "当前行"
+        } else {
+            "选中的文本"
+        };
+        let register = name.map_or(String::new(), |name| format!("到寄存器 {name}"));
+        self.set_prompt_msg(&format!("已{action}{target}{register}"));
+    }
+
+    /// 设置换行符转换后的提示消息
+    pub fn set_prompt_for_line_ending(&mut self, line_ending: LineEnding) {
+        self.set_prompt_msg(&format!("换行符已转换为 {line_ending}"));
+    }
+
+    /// 设置保存编码修改后的提示消息
+    pub fn set_prompt_for_encoding(&mut self, encoding: Encoding) {
+        // encoding_rs 按 WHATWG 标准把 Latin-1 映射为 windows-1252
+        let note = if encoding == Encoding::Latin1 {
+            "（按 windows-1252 编码）"
+        } else {
+            ""
+        };
+        self.set_prompt_msg(&format!("保存编码已改为 {encoding}{note}"));
+    }
+
+    /// 设置打开二进制文件时的警告消息
+    pub fn set_prompt_for_binary(&mut self, binary_kind: &BinaryKind) {
+        self.set_prompt_msg(&format!(
+            "二进制文件（{binary_kind}），已只读打开，ALT + R 解除只读"
+        ));
+    }
+
+    /// 设置发现遗留交换文件时的提示消息
+    pub fn set_prompt_for_recover(&mut self) {
+        self.set_prompt_msg("发现上次未保存的修改，Y 恢复，N 丢弃：");
+    }
+
+    /// 设置从交换文件恢复后的提示消息
+    pub fn set_prompt_for_recovered(&mut self) {
+        self.set_prompt_msg("已恢复上次未保存的修改，CTRL + Z 可撤销");
+    }
+
+    /// 设置文件正被另一个 ye 进程编辑时的警告消息
+    pub fn set_prompt_for_swap_busy(&mut self, pid: u32) {
+        self.set_prompt_msg(&format!(
+            "文件正在另一个 ye 进程（{pid}）中编辑，已只读打开，ALT + R 解除只读"
+        ));
+    }
+
+    /// 设置切换只读后的提示消息
+    pub fn set_prompt_for_read_only(&mut self, is_read_only: bool) {
+        self.set_prompt_msg(if is_read_only {
+            "已设为只读"
+        } else {
+            "已解除只读"
+        });
+    }
+
+    /// 设置错误消息
+    pub fn set_error(&mut self, error: &EditorError) {
+        self.set_prompt_msg(&error.to_string());
+        self.is_error = true;
+    }
+
     /// 设置提示消息，并自动更新光标的位置
     fn set_prompt_msg(&mut self, msg: &str) {
         self.prompt_msg = Line::from(msg);
+        self.is_error = false;
         self.caret = DocumentCoordinate {
             line_idx: Terminal::size().height.saturating_sub(1),
             cell_idx: self.prompt_msg.width(),
@@ -46,6 +211,7 @@ impl CmdLine {
 
     pub fn clear_prompt_msg(&mut self) {
         self.prompt_msg.clear();
+        self.is_error = false;
     }
 
     pub fn input(&self) -> &Line {
@@ -102,9 +268,17 @@ impl UI for CmdLine {
 
         // 绘制可视区域内容
         for current_row in start_row..end_row {
-            let str = format!("{}{}", self.prompt_msg.to_string(), self.input.to_string());
+            let str = format!(
+                "{}{}",
+                self.prompt_msg.to_display_string(false),
+                self.input.to_display_string(true)
+            );
 
-            Terminal::print_inverted_row(current_row, &str);
+            if self.is_error {
+                Terminal::print_error_row(current_row, &str);
+            } else {
+                Terminal::print_inverted_row(current_row, &str);
+            }
         }
     }
 }
@@ -121,6 +295,7 @@ impl Default for CmdLine {
         Self {
             size: Size::default(),
             prompt_msg,
+            is_error: false,
             input,
             caret,
         }
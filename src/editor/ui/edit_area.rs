@@ -1,35 +1,80 @@
-use std::fs;
+use std::{cmp::Ordering, fs, io::ErrorKind, mem, ops::Range};
 
 use crate::{
     Terminal,
-    editor::{Line, UI},
-    prelude::{CellIdx, DocumentCoordinate, LineIdx, Size, TerminalCoordinate},
+    editor::{Caret, Change, Document, EditorError, History, Reencoding, UI},
+    file::{BinaryKind, Encoding, FileType, LineEnding},
+    prelude::{CellIdx, ColIdx, DocumentCoordinate, LineIdx, Size, TerminalCoordinate},
 };
 
+/// 文档只读的原因
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReadOnly {
+    // 用户手动设为只读
+    Manual,
+    // 文件被识别为二进制，可以强制解除只读来编辑
+    Binary,
+    // 文件读取失败，文档是空的，解除只读后保存会用空文档覆盖原文件
+    ReadError,
+    // 另一个进程正在编辑此文件
+    SwapBusy,
+}
+
+impl ReadOnly {
+    /// 是否允许用户解除只读
+    pub fn can_unlock(self) -> bool {
+        matches!(self, Self::Manual | Self::Binary)
+    }
+}
+
 /// 编辑区
+#[derive(Default)]
 pub struct EditArea {
     // 编辑区域的尺寸
     size: Size,
     // 文档内容
-    lines: Vec<Line>,
+    document: Document,
+    // 文档的文件类型，决定自动缩进等与语言相关的行为
+    file_type: FileType,
+    // 缩进时是否插入空格（软制表符）而不是制表符
+    soft_tabs: bool,
+    // 折行宽度，重排段落和自动折行时每行不超过此宽度
+    wrap_width: usize,
+    // 是否在输入时自动折行
+    auto_fill: bool,
     // 文档是否被修改过
     is_modified: bool,
+    // 文档只读的原因，只读时拒绝一切编辑；None 表示可以编辑
+    read_only: Option<ReadOnly>,
+    // 编辑历史
+    history: History,
+    // 是否将空白字符显示为可见符号
+    show_whitespace: bool,
     // 光标在文档中的位置
     caret: DocumentCoordinate,
+    // 选区的锚点：选择文本时固定不动的一端，另一端是光标；没有选区时为 None
+    anchor: Option<DocumentCoordinate>,
+    // 其他光标（多光标编辑），编辑和移动光标时与主光标一起生效，绘制为反色的图元
+    secondary_carets: Vec<Caret>,
+    // 矩形选区的锚点和光标，以 (行, 终端列) 表示；矩形选区由每行一个光标组成，编辑或改变光标后失效
+    block: Option<((LineIdx, ColIdx), (LineIdx, ColIdx))>,
     // 滚动偏移量
     scroll_offset: TerminalCoordinate,
 }
 
 impl EditArea {
-    /// 加载文档
-    pub fn load(&mut self, file_name: &str) {
-        let contents = fs::read_to_string(file_name).unwrap_or_default();
-
-        let mut lines = Vec::new();
-        for line in contents.lines() {
-            lines.push(Line::from(line));
-        }
-        lines.push(Line::from(""));
+    /// 加载文档，二进制文件会以只读方式打开
+    /// 返回：文件为二进制时，返回其二进制特征；文件读取失败时，以只读的空文档打开并返回错误，避免保存时覆盖原文件
+    pub fn load(&mut self, file_name: &str) -> Result<Option<BinaryKind>, EditorError> {
+        let (bytes, read_error) = match fs::read(file_name) {
+            Ok(bytes) => (bytes, None),
+            // 文件不存在时视为空文档，保存时再创建文件
+            Err(err) if err.kind() == ErrorKind::NotFound => (Vec::new(), None),
+            Err(err) => (Vec::new(), Some(err)),
+        };
+        let mut document = Document::from_bytes(&bytes);
+        document.set_tab_width(self.document.tab_width());
+        let binary_kind = BinaryKind::detect(&bytes, document.format().encoding);
 
         let dirty = false;
 
@@ -38,54 +83,118 @@ impl EditArea {
             cell_idx: 0,
         };
 
-        self.lines = lines;
+        self.document = document;
         self.is_modified = dirty;
+        self.read_only = if read_error.is_some() {
+            Some(ReadOnly::ReadError)
+        } else {
+            binary_kind.as_ref().map(|_| ReadOnly::Binary)
+        };
+        self.history = History::default();
         self.caret = caret;
+        self.anchor = None;
+        self.secondary_carets.clear();
+        self.block = None;
+
+        match read_error {
+            Some(err) => Err(EditorError::ReadFile(err)),
+            None => Ok(binary_kind),
+        }
     }
 
     pub fn size(&self) -> &Size {
         &self.size
     }
 
-    /// 获取可变的行列表引用
-    pub fn mut_lines(&mut self) -> &mut Vec<Line> {
-        &mut self.lines
+    pub fn set_file_type(&mut self, file_type: FileType) {
+        self.file_type = file_type;
+    }
+
+    pub fn file_type(&self) -> &FileType {
+        &self.file_type
+    }
+
+    /// 设置本文档的缩进风格：制表符宽度，以及缩进时插入空格还是制表符
+    pub fn set_indent_style(&mut self, tab_width: usize, soft_tabs: bool) {
+        self.document.set_tab_width(tab_width);
+        self.soft_tabs = soft_tabs;
+    }
+
+    /// 设置折行宽度，以及是否在输入时自动折行
+    pub fn set_wrap(&mut self, wrap_width: usize, auto_fill: bool) {
+        self.wrap_width = wrap_width;
+        self.auto_fill = auto_fill;
+    }
+
+    pub fn wrap_width(&self) -> usize {
+        self.wrap_width
+    }
+
+    pub fn is_auto_fill(&self) -> bool {
+        self.auto_fill
     }
 
-    /// 获取行列表的引用
-    pub fn lines(&self) -> &Vec<Line> {
-        &self.lines
+    /// 切换是否自动折行，返回切换后的状态
+    pub fn toggle_auto_fill(&mut self) -> bool {
+        self.auto_fill = !self.auto_fill;
+        self.auto_fill
     }
 
-    /// 获取行列表的长度
+    pub fn is_soft_tabs(&self) -> bool {
+        self.soft_tabs
+    }
+
+    /// 一级缩进：软制表符时为制表符宽度个空格，否则为一个制表符
+    pub fn indent_unit(&self) -> String {
+        if self.soft_tabs {
+            " ".repeat(self.document.tab_width())
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// 获取文档的引用
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// 获取文档的可变引用
+    pub fn mut_document(&mut self) -> &mut Document {
+        &mut self.document
+    }
+
+    /// 获取文档的总行数
     pub fn lines_len(&self) -> usize {
-        self.lines.len()
+        self.document.lines_len()
     }
 
     /// 获取指定行的图元数量
     pub fn line_cell_count(&self, line_idx: LineIdx) -> usize {
-        self.lines.get(line_idx).map_or(0, Line::cells_count)
+        self.document.line_cell_count(line_idx)
     }
 
     /// 获取指定行的 [行首，指定索引位置) 的所有图元占据的终端列宽
     pub fn line_cell_width_until(&self, line_idx: LineIdx, cell_idx: CellIdx) -> usize {
-        self.lines
-            .get(line_idx)
+        self.document
+            .line(line_idx)
             .map_or(0, |line| line.width_until(cell_idx))
     }
 
-    /// 获取光标所在的那一行的引用
-    pub fn line_on_caret(&self) -> Option<&Line> {
-        self.lines.get(self.caret.line_idx)
+    /// 切换空白字符是否显示为可见符号，只影响渲染，不影响文档内容
+    pub fn toggle_show_whitespace(&mut self) {
+        self.show_whitespace = !self.show_whitespace;
     }
 
-    /// 获取光标所在的那一行的可变引用
-    pub fn mut_line_on_caret(&mut self) -> Option<&mut Line> {
-        self.lines.get_mut(self.caret.line_idx)
+    pub fn set_read_only(&mut self, read_only: Option<ReadOnly>) {
+        self.read_only = read_only;
     }
 
-    pub fn set_is_modified(&mut self, is_dirty: bool) {
-        self.is_modified = is_dirty;
+    pub fn read_only(&self) -> Option<ReadOnly> {
+        self.read_only
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.is_some()
     }
 
     pub fn is_modified(&self) -> bool {
@@ -96,6 +205,241 @@ impl EditArea {
         self.caret = position;
     }
 
+    /// 以当前光标位置为锚点开始选择，已有选区时保持原锚点
+    pub fn start_selection(&mut self) {
+        if self.anchor.is_none() {
+            self.anchor = Some(self.caret.clone());
+        }
+    }
+
+    /// 取消选区
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+        self.block = None;
+    }
+
+    /// 所有光标的位置，包括主光标
+    pub fn caret_positions(&self) -> Vec<DocumentCoordinate> {
+        self.secondary_carets
+            .iter()
+            .map(|caret| caret.position.clone())
+            .chain([self.caret.clone()])
+            .collect()
+    }
+
+    /// 添加一个光标，与已有光标重叠时合并
+    pub fn add_caret(&mut self, caret: Caret) {
+        self.block = None;
+        self.secondary_carets.push(caret);
+        self.merge_carets();
+    }
+
+    /// 是否有多个光标
+    pub fn has_secondary_carets(&self) -> bool {
+        !self.secondary_carets.is_empty()
+    }
+
+    /// 只保留主光标
+    pub fn clear_secondary_carets(&mut self) {
+        self.secondary_carets.clear();
+        self.block = None;
+    }
+
+    /// 所有光标的选区范围（没有选区时为光标所在的空范围），按在文档中的位置排序
+    pub fn caret_ranges(&self) -> Vec<(DocumentCoordinate, DocumentCoordinate)> {
+        let primary = Caret {
+            position: self.caret.clone(),
+            anchor: self.anchor.clone(),
+        };
+        let mut ranges: Vec<_> = self
+            .secondary_carets
+            .iter()
+            .chain([&primary])
+            .map(Caret::range)
+            .collect();
+        ranges.sort();
+
+        ranges
+    }
+
+    /// 主光标在所有光标中按文档位置排第几，for_each_caret 最后处理主光标
+    pub fn primary_caret_rank(&self) -> usize {
+        self.secondary_carets
+            .iter()
+            .filter(|caret| caret.position < self.caret)
+            .count()
+    }
+
+    /// 矩形选区的锚点和光标，不在矩形选择中时返回 None
+    pub fn block_selection(&self) -> Option<((LineIdx, ColIdx), (LineIdx, ColIdx))> {
+        self.block
+    }
+
+    /// 选中以 anchor 和 cursor 为对角的矩形，每行一个光标，主光标在 cursor 所在行
+    /// 矩形按终端列划分，部分落在矩形中的宽图元整个选中；比矩形左边界短的行，光标停在行尾
+    pub fn set_block_selection(&mut self, anchor: (LineIdx, ColIdx), cursor: (LineIdx, ColIdx)) {
+        let (first, last) = (anchor.0.min(cursor.0), anchor.0.max(cursor.0));
+        let (left, right) = (anchor.1.min(cursor.1), anchor.1.max(cursor.1));
+        let is_forward = cursor.1 >= anchor.1;
+
+        self.secondary_carets.clear();
+        for line_idx in first..=last {
+            let line = self.document.line(line_idx).unwrap_or_default();
+            let start = DocumentCoordinate {
+                line_idx,
+                cell_idx: line.cell_at_col(left, false),
+            };
+            let end = DocumentCoordinate {
+                line_idx,
+                cell_idx: line.cell_at_col(right, true),
+            };
+
+            let (anchor, position) = if is_forward {
+                (start, end)
+            } else {
+                (end, start)
+            };
+            let caret = Caret {
+                anchor: (anchor != position).then_some(anchor),
+                position,
+            };
+
+            if line_idx == cursor.0 {
+                self.caret = caret.position;
+                self.anchor = caret.anchor;
+            } else {
+                self.secondary_carets.push(caret);
+            }
+        }
+
+        self.block = Some((anchor, cursor));
+    }
+
+    /// 对每个光标依次执行 edit：执行时该光标及其选区临时作为主光标，所有改动作为一次编辑记录
+    /// 每次改动后，其余光标会随文本移动（见 replace_text），最后合并重叠的光标
+    pub fn for_each_caret(&mut self, mut edit: impl FnMut(&mut Self)) {
+        if self.secondary_carets.is_empty() {
+            edit(self);
+            return;
+        }
+
+        self.transact(|edit_area| {
+            for idx in 0..edit_area.secondary_carets.len() {
+                edit_area.swap_caret(idx);
+                edit(edit_area);
+                edit_area.swap_caret(idx);
+            }
+            // 最后处理主光标，使文本滚动到主光标处
+            edit(edit_area);
+        });
+        self.merge_carets();
+    }
+
+    /// 交换主光标与指定的其他光标
+    fn swap_caret(&mut self, idx: usize) {
+        let caret = &mut self.secondary_carets[idx];
+        mem::swap(&mut self.caret, &mut caret.position);
+        mem::swap(&mut self.anchor, &mut caret.anchor);
+    }
+
+    /// 合并重叠的光标
+    fn merge_carets(&mut self) {
+        let primary = Caret {
+            position: self.caret.clone(),
+            anchor: self.anchor.clone(),
+        };
+        let carets = self
+            .secondary_carets
+            .drain(..)
+            .map(|caret| (caret, false))
+            .chain([(primary, true)])
+            .collect();
+
+        for (caret, is_primary) in Caret::merge(carets) {
+            if is_primary {
+                self.caret = caret.position;
+                self.anchor = caret.anchor;
+            } else {
+                self.secondary_carets.push(caret);
+            }
+        }
+    }
+
+    /// 选区的范围 [start, end)，没有选区或选区为空时返回 None
+    pub fn selection(&self) -> Option<(DocumentCoordinate, DocumentCoordinate)> {
+        let anchor = self.anchor.clone()?;
+        let caret = self.caret.clone();
+
+        match anchor.cmp(&caret) {
+            Ordering::Less => Some((anchor, caret)),
+            Ordering::Greater => Some((caret, anchor)),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// 指定行中需要反色显示的图元范围：主光标的选区，以及其他光标的选区和光标所在的图元
+    fn highlighted_cells(&self, line_idx: LineIdx) -> Vec<Range<CellIdx>> {
+        let mut highlights: Vec<Range<CellIdx>> = self
+            .selection()
+            .and_then(|(start, end)| Self::selected_cells(line_idx, &start, &end))
+            .into_iter()
+            .collect();
+
+        for caret in &self.secondary_carets {
+            let (start, end) = caret.range();
+            highlights.extend(Self::selected_cells(line_idx, &start, &end));
+
+            if caret.position.line_idx == line_idx {
+                let cell_idx = caret.position.cell_idx;
+                highlights.push(cell_idx..cell_idx.saturating_add(1));
+            }
+        }
+
+        highlights
+    }
+
+    /// 光标旁括号的配对括号位置：优先看光标处的图元，其次看光标前的图元
+    fn matching_bracket(&self) -> Option<DocumentCoordinate> {
+        let brackets = self.file_type.brackets();
+        let before = self
+            .caret
+            .cell_idx
+            .checked_sub(1)
+            .map(|cell_idx| DocumentCoordinate {
+                line_idx: self.caret.line_idx,
+                cell_idx,
+            });
+
+        [Some(self.caret.clone()), before]
+            .into_iter()
+            .flatten()
+            .find_map(|at| self.document.matching_bracket(&at, brackets))
+    }
+
+    /// 选区 [start, end) 在指定行中的图元范围，选区跨过行尾时范围包含行尾的换行符
+    fn selected_cells(
+        line_idx: LineIdx,
+        start: &DocumentCoordinate,
+        end: &DocumentCoordinate,
+    ) -> Option<Range<CellIdx>> {
+        if start == end || line_idx < start.line_idx || line_idx > end.line_idx {
+            return None;
+        }
+
+        let from = if line_idx == start.line_idx {
+            start.cell_idx
+        } else {
+            0
+        };
+        let to = if line_idx == end.line_idx {
+            end.cell_idx
+        } else {
+            CellIdx::MAX
+        };
+
+        Some(from..to)
+    }
+
     /// 获取光标在文档中的位置
     pub fn caret(&self) -> &DocumentCoordinate {
         &self.caret
@@ -121,12 +465,242 @@ impl EditArea {
         &mut self.scroll_offset
     }
 
-    /// 移除指定行，并返回指定行，返回一定不会为空
-    pub fn remove_line(&mut self, line_idx: usize) -> Line {
-        if line_idx >= self.lines.len() {
-            return Line::default();
+    /// 编辑文档：将 [start, end) 范围内的文本替换为 text，并记录到编辑历史中
+    /// 返回：替换后 text 末尾所在的位置，文档只读时不做任何编辑，返回光标当前的位置
+    pub fn replace_text(
+        &mut self,
+        start: DocumentCoordinate,
+        end: DocumentCoordinate,
+        text: &str,
+    ) -> DocumentCoordinate {
+        if self.is_read_only() {
+            return self.caret.clone();
+        }
+        // 文本改动后原选区失效
+        self.anchor = None;
+        self.block = None;
+
+        // 历史记录使用编辑前的字符索引，不受编辑后字素合并的影响
+        let start_char = self.document.coordinate_to_char(&start);
+        let caret_before = self.document.coordinate_to_char(&self.caret);
+        let removed = self.document.remove(&start, &end);
+        let text_end = self.document.insert(&start, text);
+        // 其他光标随文本移动
+        for caret in &mut self.secondary_carets {
+            caret.shift(&start, &end, &text_end);
+        }
+
+        if !removed.is_empty() || !text.is_empty() {
+            let caret_after = start_char.saturating_add(text.chars().count());
+            self.history.record(Change {
+                start: start_char,
+                removed,
+                inserted: text.to_string(),
+                caret_before,
+                caret_after,
+                reencoding: None,
+            });
+            self.is_modified = self.history.is_modified();
+        }
+
+        text_end
+    }
+
+    /// 把行 [first, last] 替换为 lines，作为一次可撤销的编辑
+    /// 行之间沿用原来同一位置的换行符，行数增加时多出的行使用文档的换行风格
+    /// lines 为空时连同换行符一起删除这些行；原来有选区时选中替换后的行，否则光标停在原位置
+    pub fn replace_lines(&mut self, first: LineIdx, last: LineIdx, lines: &[String]) {
+        let had_selection = self.selection().is_some();
+        let caret = self.caret.clone();
+
+        let mut start = DocumentCoordinate {
+            line_idx: first,
+            cell_idx: 0,
+        };
+        let mut end = DocumentCoordinate {
+            line_idx: last,
+            cell_idx: self.line_cell_count(last),
+        };
+        if lines.is_empty() {
+            if last.saturating_add(1) < self.lines_len() {
+                end = DocumentCoordinate {
+                    line_idx: last.saturating_add(1),
+                    cell_idx: 0,
+                };
+            } else if let Some(prev) = first.checked_sub(1) {
+                start = DocumentCoordinate {
+                    line_idx: prev,
+                    cell_idx: self.line_cell_count(prev),
+                };
+            }
+        }
+
+        let line_endings: Vec<LineEnding> = (first..last)
+            .filter_map(|line_idx| self.document.line_ending_of(line_idx))
+            .collect();
+        let mut text = String::new();
+        for (idx, line) in lines.iter().enumerate() {
+            if idx > 0 {
+                let line_ending = line_endings
+                    .get(idx.saturating_sub(1))
+                    .copied()
+                    .unwrap_or(self.document.line_ending());
+                text.push_str(line_ending.as_str());
+            }
+            text.push_str(line);
+        }
+        let text_end = self.replace_text(start.clone(), end, &text);
+        self.secondary_carets.clear();
+
+        if had_selection && !lines.is_empty() {
+            self.anchor = Some(start);
+            self.caret = text_end;
+        } else {
+            let line_idx = caret.line_idx.min(self.lines_len().saturating_sub(1));
+            self.caret = DocumentCoordinate {
+                line_idx,
+                cell_idx: caret.cell_idx.min(self.line_cell_count(line_idx)),
+            };
         }
-        return self.lines.remove(line_idx);
+    }
+
+    /// 用 text 替换整个文档，作为一次可撤销的编辑，并重新检测换行风格
+    pub fn replace_all(&mut self, text: &str) {
+        let last_line_idx = self.lines_len().saturating_sub(1);
+        let end = DocumentCoordinate {
+            line_idx: last_line_idx,
+            cell_idx: self.line_cell_count(last_line_idx),
+        };
+
+        self.replace_text(DocumentCoordinate::default(), end, text);
+        self.document.detect_line_endings();
+    }
+
+    /// 将 edit 中的所有改动作为一个整体记录，撤销和重做时一步完成
+    pub fn transact(&mut self, edit: impl FnOnce(&mut Self)) {
+        self.history.begin();
+        edit(self);
+        self.history.commit();
+        self.is_modified = self.history.is_modified();
+    }
+
+    /// 撤销最近一次编辑，并把光标恢复到编辑前的位置，只读或没有可撤销的编辑时返回 false
+    pub fn undo(&mut self) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+        let Some(changes) = self.history.undo() else {
+            return false;
+        };
+        self.anchor = None;
+        self.secondary_carets.clear();
+        self.block = None;
+
+        for change in changes.iter().rev() {
+            self.document
+                .remove_chars(change.start, change.inserted_end());
+            self.document.insert_at_char(change.start, &change.removed);
+            if let Some(Reencoding {
+                before: (encoding, bom),
+                ..
+            }) = change.reencoding
+            {
+                self.document.set_encoding(encoding, bom);
+            }
+        }
+        if let Some(first) = changes.first() {
+            self.caret = self.document.char_to_coordinate(first.caret_before);
+        }
+        if changes.iter().any(Change::crosses_lines) {
+            self.document.detect_line_endings();
+        }
+        self.is_modified = self.history.is_modified();
+
+        true
+    }
+
+    /// 重做最近一次撤销的编辑，并把光标恢复到编辑后的位置，只读或没有可重做的编辑时返回 false
+    pub fn redo(&mut self) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+        let Some(changes) = self.history.redo() else {
+            return false;
+        };
+        self.anchor = None;
+        self.secondary_carets.clear();
+        self.block = None;
+
+        for change in changes.iter() {
+            self.document
+                .remove_chars(change.start, change.removed_end());
+            self.document.insert_at_char(change.start, &change.inserted);
+            if let Some(Reencoding {
+                after: (encoding, bom),
+                ..
+            }) = change.reencoding
+            {
+                self.document.set_encoding(encoding, bom);
+            }
+        }
+        if let Some(last) = changes.last() {
+            self.caret = self.document.char_to_coordinate(last.caret_after);
+        }
+        if changes.iter().any(Change::crosses_lines) {
+            self.document.detect_line_endings();
+        }
+        self.is_modified = self.history.is_modified();
+
+        true
+    }
+
+    /// 将文档中所有的换行符转换为指定的换行风格，作为一次编辑记录到编辑历史中
+    /// 一次性生成转换后的全文再替换，逐行替换时写入的 CR 会与下一行的 LF 合并为 CRLF，导致行号错位
+    /// 转换不改变行数和图元，光标保持原位
+    pub fn convert_line_endings(&mut self, line_ending: LineEnding) {
+        let text = self.document.to_text();
+        let converted = line_ending.normalize(&text);
+        if converted == text {
+            return;
+        }
+
+        let secondary_carets = mem::take(&mut self.secondary_carets);
+        self.transact(|edit_area| edit_area.replace_all(&converted));
+        self.secondary_carets = secondary_carets;
+    }
+
+    /// 修改文档保存时使用的字符编码，作为一次编辑记录到编辑历史中，撤销时恢复原来的编码
+    pub fn set_encoding(&mut self, encoding: Encoding, bom: bool) {
+        let format = self.document.format();
+        let before = (format.encoding, format.bom);
+        if before == (encoding, bom) {
+            return;
+        }
+
+        self.document.set_encoding(encoding, bom);
+        if self.is_read_only() {
+            return;
+        }
+
+        let caret = self.document.coordinate_to_char(&self.caret);
+        self.history.record(Change {
+            start: caret,
+            removed: String::new(),
+            inserted: String::new(),
+            caret_before: caret,
+            caret_after: caret,
+            reencoding: Some(Reencoding {
+                before,
+                after: (encoding, bom),
+            }),
+        });
+        self.is_modified = self.history.is_modified();
+    }
+
+    /// 文档已保存，记录保存时的编辑历史位置
+    pub fn mark_saved(&mut self) {
+        self.history.mark_saved();
+        self.is_modified = false;
     }
 }
 
@@ -136,37 +710,144 @@ impl UI for EditArea {
     }
 
     fn draw(&mut self, start_row: usize) {
-        if !self.lines.is_empty() {
-            // 编辑区域的结束行（此行不绘制任何东西）
-            let end_row = start_row.saturating_add(self.size.height);
-
-            for current_row in start_row..end_row {
-                let line_idx = current_row
-                    .saturating_sub(start_row)
-                    .saturating_add(self.scroll_offset.row);
-
-                Terminal::print_row(
-                    current_row,
-                    &self.lines.get(line_idx).map_or("~".to_string(), |line| {
+        // 编辑区域的结束行（此行不绘制任何东西）
+        let end_row = start_row.saturating_add(self.size.height);
+        let bracket = self.matching_bracket();
+
+        // 只为可视区域内的行构建图行
+        for current_row in start_row..end_row {
+            let line_idx = current_row
+                .saturating_sub(start_row)
+                .saturating_add(self.scroll_offset.row);
+
+            let mut highlights = self.highlighted_cells(line_idx);
+            if let Some(bracket) = bracket.as_ref().filter(|at| at.line_idx == line_idx) {
+                highlights.push(bracket.cell_idx..bracket.cell_idx.saturating_add(1));
+            }
+
+            Terminal::print_row(
+                current_row,
+                &self
+                    .document
+                    .line(line_idx)
+                    .map_or("~".to_string(), |line| {
                         line.get_visible_substr(
                             self.scroll_offset.col,
                             self.scroll_offset.col.saturating_add(self.size.width),
+                            self.show_whitespace,
+                            &highlights,
                         )
                     }),
-                );
-            }
+            );
         }
     }
 }
 
-impl Default for EditArea {
-    fn default() -> Self {
-        Self {
-            size: Size::default(),
-            lines: Vec::new(),
-            is_modified: false,
-            caret: DocumentCoordinate::default(),
-            scroll_offset: TerminalCoordinate::default(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_line_endings_handles_mixed_endings() {
+        let cases = [
+            (LineEnding::Cr, "a\r\rb\rc\rd\r"),
+            (LineEnding::Lf, "a\n\nb\nc\nd\n"),
+            (LineEnding::Crlf, "a\r\n\r\nb\r\nc\r\nd\r\n"),
+        ];
+
+        for (line_ending, expected) in cases {
+            let mut edit_area = EditArea::default();
+            edit_area.replace_all("a\n\nb\r\nc\rd\n");
+            let lines_len = edit_area.lines_len();
+
+            edit_area.convert_line_endings(line_ending);
+
+            assert_eq!(edit_area.document().to_text(), expected);
+            assert_eq!(edit_area.lines_len(), lines_len);
+            assert!(edit_area.document().line_ending() == line_ending);
         }
     }
+
+    #[test]
+    fn convert_line_endings_is_one_undo_step() {
+        let mut edit_area = EditArea::default();
+        edit_area.replace_all("a\n\nb");
+
+        edit_area.convert_line_endings(LineEnding::Cr);
+        assert_eq!(edit_area.document().to_text(), "a\r\rb");
+
+        assert!(edit_area.undo());
+        assert_eq!(edit_area.document().to_text(), "a\n\nb");
+    }
+
+    #[test]
+    fn undo_combining_mark_restores_text() {
+        let mut edit_area = EditArea::default();
+        edit_area.replace_all("e");
+        let at = DocumentCoordinate {
+            line_idx: 0,
+            cell_idx: 1,
+        };
+
+        edit_area.replace_text(at.clone(), at, "\u{301}");
+        assert_eq!(edit_area.document().to_text(), "e\u{301}");
+
+        assert!(edit_area.undo());
+        assert_eq!(edit_area.document().to_text(), "e");
+
+        assert!(edit_area.redo());
+        assert_eq!(edit_area.document().to_text(), "e\u{301}");
+    }
+
+    #[test]
+    fn undo_and_redo_are_ignored_when_read_only() {
+        let mut edit_area = EditArea::default();
+        edit_area.replace_all("a");
+        edit_area.replace_all("b");
+        assert!(edit_area.undo());
+
+        edit_area.set_read_only(Some(ReadOnly::Manual));
+        assert!(!edit_area.redo());
+        assert!(!edit_area.undo());
+        assert_eq!(edit_area.document().to_text(), "a");
+    }
+
+    #[test]
+    fn replace_lines_keeps_line_endings_in_place() {
+        let mut edit_area = EditArea::default();
+        edit_area.replace_all("c\r\nb\na\r\n");
+
+        let lines = ["a", "b", "c"].map(String::from);
+        edit_area.replace_lines(0, 2, &lines);
+
+        assert_eq!(edit_area.document().to_text(), "a\r\nb\nc\r\n");
+    }
+
+    #[test]
+    fn set_encoding_is_an_undoable_modification() {
+        let mut edit_area = EditArea::default();
+        edit_area.mark_saved();
+
+        edit_area.set_encoding(Encoding::Latin1, false);
+        assert!(edit_area.is_modified());
+
+        assert!(edit_area.undo());
+        assert!(edit_area.document().format().encoding == Encoding::Utf8);
+        assert!(!edit_area.is_modified());
+
+        assert!(edit_area.redo());
+        assert!(edit_area.document().format().encoding == Encoding::Latin1);
+        assert!(edit_area.is_modified());
+    }
+
+    #[test]
+    fn load_failure_is_locked_read_only() {
+        let mut edit_area = EditArea::default();
+        // 读取目录会失败，但错误不是 NotFound
+        assert!(edit_area.load(env!("CARGO_MANIFEST_DIR")).is_err());
+
+        let read_only = edit_area.read_only();
+        assert!(read_only == Some(ReadOnly::ReadError));
+        assert!(!read_only.is_some_and(ReadOnly::can_unlock));
+    }
 }
@@ -2,7 +2,7 @@ mod cmd_line;
 mod edit_area;
 mod status_bar;
 pub use cmd_line::CmdLine;
-pub use edit_area::EditArea;
+pub use edit_area::{EditArea, ReadOnly};
 pub use status_bar::StatusBar;
 
 use crate::prelude::Size;
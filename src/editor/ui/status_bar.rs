@@ -1,14 +1,18 @@
 use crate::{
     Terminal,
     editor::{FileInfo, UI},
+    file::FileFormat,
     prelude::{DocumentCoordinate, Size},
 };
 
+#[derive(Default)]
 pub struct StatusBar {
     size: Size,
     file_info: FileInfo,
+    file_format: FileFormat,
     total_lines: usize,
     is_modified: bool,
+    is_read_only: bool,
     caret: DocumentCoordinate,
 }
 
@@ -20,19 +24,24 @@ impl StatusBar {
     pub fn update_status(
         &mut self,
         file_info: FileInfo,
+        file_format: FileFormat,
         total_lines: usize,
         is_modified: bool,
+        is_read_only: bool,
         caret: DocumentCoordinate,
     ) {
         self.file_info = file_info;
+        self.file_format = file_format;
         self.total_lines = total_lines;
         self.is_modified = is_modified;
+        self.is_read_only = is_read_only;
         self.caret = caret;
     }
 
     /// 更新状态栏信息
     /// 1. 文件路径
-    /// 2. 文档名称 - 文档总行数 lines (是否修改了文档) 空白填充 光标位置行:列 | 文档类型
+    /// 2. 文档名称 - 文档总行数 lines (是否修改了文档)(是否只读) 空白填充 光标位置行:列 | 文件格式 | 文档类型
+    ///
     /// 返回：更新后的状态信息
     fn status_to_string(&self) -> Vec<String> {
         let mut result = Vec::new();
@@ -40,20 +49,22 @@ impl StatusBar {
         // 文档路径
         result.push(self.file_info.get_path_str());
 
-        // 文档名称 - 文档总行数 lines (是否修改了文档) 空白填充 光标位置行:列 | 文档类型
-        // 左：文件名.后缀 - 总行数 lines (是否修改了文件)
+        // 文档名称 - 文档总行数 lines (是否修改了文档)(是否只读) 空白填充 光标位置行:列 | 文件格式 | 文档类型
+        // 左：文件名.后缀 - 总行数 lines (是否修改了文件)(是否只读)
         let left = format!(
-            "{} - {} lines {}",
+            "{} - {} lines {}{}",
             self.file_info.get_name(),
             self.total_lines,
-            Self::is_modified_to_string(self.is_modified)
+            Self::is_modified_to_string(self.is_modified),
+            Self::is_read_only_to_string(self.is_read_only)
         );
 
-        // 右：光标所在终端网格的“行:列” | 文件类型
+        // 右：光标所在终端网格的“行:列” | 文件格式 | 文件类型
         let right = format!(
-            "{} | {}",
+            "{} | {} | {}",
             Self::caret_to_string(&self.caret),
-            self.file_info.get_file_type().to_string()
+            self.file_format,
+            self.file_info.get_file_type()
         );
 
         // 根据状态栏的宽度，在中间填充空白
@@ -76,6 +87,14 @@ impl StatusBar {
         }
     }
 
+    fn is_read_only_to_string(is_read_only: bool) -> String {
+        if is_read_only {
+            "(read-only)".to_string()
+        } else {
+            String::new()
+        }
+    }
+
     // 光标位置转为字符串
     fn caret_to_string(caret: &DocumentCoordinate) -> String {
         format!(
@@ -114,15 +133,3 @@ impl UI for StatusBar {
         }
     }
 }
-
-impl Default for StatusBar {
-    fn default() -> Self {
-        Self {
-            size: Size::default(),
-            file_info: FileInfo::default(),
-            total_lines: 0,
-            is_modified: false,
-            caret: DocumentCoordinate::default(),
-        }
-    }
-}
@@ -0,0 +1,124 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+// 解析符号链接的最大层数，超过时认为链接成环
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+/// 原子保存：先把内容完整写入同目录下的临时文件并落盘，再用重命名替换目标文件
+/// 写入过程中出现任何错误，目标文件都保持原样，不会出现写了一半的文件
+pub struct AtomicSave {}
+
+impl AtomicSave {
+    /// 将 bytes 原子地写入 path
+    /// path 是符号链接时写入其指向的文件，并保留原文件的权限和所有者；
+    /// backup 为 true 时，把原文件的上一个版本保存为 `文件名~`
+    pub fn write(path: &Path, bytes: &[u8], backup: bool) -> io::Result<()> {
+        let target = Self::resolve_symlink(path)?;
+        // 目标文件不存在时，按新文件处理
+        let metadata = fs::metadata(&target).ok();
+
+        if backup && metadata.is_some() {
+            fs::copy(&target, Self::sibling_path(&target, "", "~"))?;
+        }
+
+        let temp_path = Self::sibling_path(&target, ".", &format!(".ye-{}.tmp", process::id()));
+        let result = Self::write_temp(&temp_path, bytes, metadata.as_ref())
+            .and_then(|_| fs::rename(&temp_path, &target));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+            return result;
+        }
+
+        Self::sync_parent_dir(&target);
+        Ok(())
+    }
+
+    /// 把内容写入临时文件，同步到磁盘，并沿用原文件的权限和所有者
+    fn write_temp(
+        temp_path: &Path,
+        bytes: &[u8],
+        metadata: Option<&fs::Metadata>,
+    ) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(temp_path)?;
+
+        file.write_all(bytes)?;
+
+        if let Some(metadata) = metadata {
+            file.set_permissions(metadata.permissions())?;
+            Self::copy_owner(&file, metadata);
+        }
+
+        file.sync_all()
+    }
+
+    /// 沿用原文件的所有者，没有权限修改所有者时保持当前用户
+    #[cfg(unix)]
+    fn copy_owner(file: &File, metadata: &fs::Metadata) {
+        use std::os::unix::fs::{MetadataExt, fchown};
+
+        let _ = fchown(file, Some(metadata.uid()), Some(metadata.gid()));
+    }
+
+    #[cfg(not(unix))]
+    fn copy_owner(_: &File, _: &fs::Metadata) {}
+
+    /// 把目录项的改动（重命名）也同步到磁盘
+    #[cfg(unix)]
+    fn sync_parent_dir(target: &Path) {
+        if let Some(parent) = target.parent()
+            && let Ok(dir) = File::open(Self::non_empty_dir(parent))
+        {
+            let _ = dir.sync_all();
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn sync_parent_dir(_: &Path) {}
+
+    /// 解析符号链接（包括多级链接），返回最终要写入的文件路径
+    fn resolve_symlink(path: &Path) -> io::Result<PathBuf> {
+        let mut target = path.to_path_buf();
+
+        for _ in 0..MAX_SYMLINK_DEPTH {
+            if !fs::symlink_metadata(&target).is_ok_and(|metadata| metadata.is_symlink()) {
+                return Ok(target);
+            }
+
+            let link = fs::read_link(&target)?;
+            target = match target.parent() {
+                Some(parent) if link.is_relative() => parent.join(link),
+                _ => link,
+            };
+        }
+
+        Err(io::Error::other("符号链接层数过多"))
+    }
+
+    /// 与 path 同目录、在文件名前后加上前缀和后缀的路径
+    fn sibling_path(path: &Path, prefix: &str, suffix: &str) -> PathBuf {
+        let mut file_name = OsString::from(prefix);
+        file_name.push(path.file_name().unwrap_or_default());
+        file_name.push(suffix);
+
+        path.with_file_name(file_name)
+    }
+
+    /// 相对路径的文件没有父目录部分时，父目录就是当前目录
+    #[cfg(unix)]
+    fn non_empty_dir(dir: &Path) -> &Path {
+        if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        }
+    }
+}
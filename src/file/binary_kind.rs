@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::file::Encoding;
+
+// 只检测文件开头的这部分字节，避免大文件打开时过慢
+const SAMPLE_LEN: usize = 8192;
+
+// 常见二进制文件的魔数；Windows 可执行文件的 "MZ" 太短，容易与文本混淆，另行检测
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x7fELF", "ELF 可执行文件"),
+    (b"\xcf\xfa\xed\xfe", "Mach-O 可执行文件"),
+    (b"\xca\xfe\xba\xbe", "Java 字节码"),
+    (b"\0asm", "WebAssembly 模块"),
+    (b"\x89PNG\r\n\x1a\n", "PNG 图片"),
+    (b"\xff\xd8\xff", "JPEG 图片"),
+    (b"GIF87a", "GIF 图片"),
+    (b"GIF89a", "GIF 图片"),
+    (b"%PDF-", "PDF 文档"),
+    (b"PK\x03\x04", "ZIP 压缩包"),
+    (b"\x1f\x8b", "GZIP 压缩包"),
+    (b"7z\xbc\xaf\x27\x1c", "7z 压缩包"),
+    (b"SQLite format 3\0", "SQLite 数据库"),
+];
+
+/// 二进制文件的特征
+pub enum BinaryKind {
+    // 文件开头是已知的二进制格式魔数
+    Magic(&'static str),
+    // 文件中有 NUL 字节
+    NulBytes,
+    // 文件中有大量无法作为 UTF-8 解析的字节
+    InvalidText,
+}
+
+impl BinaryKind {
+    /// 检测文件内容是否为二进制，encoding 为文件内容被检测出的编码
+    pub fn detect(bytes: &[u8], encoding: Encoding) -> Option<Self> {
+        if let Some((_, name)) = MAGIC_NUMBERS
+            .iter()
+            .find(|(magic, _)| bytes.starts_with(magic))
+        {
+            return Some(Self::Magic(name));
+        }
+        if Self::is_portable_executable(bytes) {
+            return Some(Self::Magic("Windows 可执行文件"));
+        }
+
+        // UTF-16 文本本身就包含大量 NUL 字节
+        if matches!(encoding, Encoding::Utf16Le | Encoding::Utf16Be) {
+            return None;
+        }
+
+        let sample = &bytes[..bytes.len().min(SAMPLE_LEN)];
+
+        if sample.contains(&0) {
+            return Some(Self::NulBytes);
+        }
+
+        // 只有兜底解码为 Latin-1 时才检查，能按 UTF-8 或 GB18030 解码的都是文本
+        if encoding == Encoding::Latin1 {
+            let invalid_len: usize = sample
+                .utf8_chunks()
+                .map(|chunk| chunk.invalid().len())
+                .sum();
+
+            // 超过三成的字节不是合法的 UTF-8
+            if invalid_len * 10 > sample.len() * 3 {
+                return Some(Self::InvalidText);
+            }
+        }
+
+        None
+    }
+
+    /// Windows 可执行文件（PE）：以 "MZ" 开头，且 0x3C 处的 e_lfanew 指向 "PE\0\0" 签名
+    fn is_portable_executable(bytes: &[u8]) -> bool {
+        if !bytes.starts_with(b"MZ") {
+            return false;
+        }
+
+        let Some(e_lfanew) = bytes.get(0x3C..0x40) else {
+            return false;
+        };
+        let offset = u32::from_le_bytes([e_lfanew[0], e_lfanew[1], e_lfanew[2], e_lfanew[3]]);
+
+        usize::try_from(offset)
+            .ok()
+            .and_then(|offset| bytes.get(offset..offset.checked_add(4)?))
+            .is_some_and(|signature| signature == b"PE\0\0")
+    }
+}
+
+impl Display for BinaryKind {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Magic(name) => write!(formatter, "{name}"),
+            Self::NulBytes => write!(formatter, "包含 NUL 字节"),
+            Self::InvalidText => write!(formatter, "包含大量非文本字节"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_starting_with_mz_is_not_binary() {
+        let text =
+            b"MZ is a postal code prefix\nand this is plain text, long enough to reach 0x40.\n";
+        assert!(BinaryKind::detect(text, Encoding::Utf8).is_none());
+    }
+
+    #[test]
+    fn portable_executable_is_binary() {
+        let mut bytes = vec![0u8; 0x84];
+        bytes[..2].copy_from_slice(b"MZ");
+        bytes[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        bytes[0x80..].copy_from_slice(b"PE\0\0");
+
+        assert!(matches!(
+            BinaryKind::detect(&bytes, Encoding::Latin1),
+            Some(BinaryKind::Magic(_))
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt::{Display, Formatter, Result};
+
+use encoding_rs::{GB18030, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+// 各种编码的字节顺序标记（BOM）
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// 文件的字符编码
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    // GB18030 兼容 GBK 与 GB2312
+    Gb18030,
+    Latin1,
+}
+
+impl Encoding {
+    /// 检测字节的编码并解码为文本
+    /// 检测顺序：BOM -> UTF-16（无 BOM，根据 NUL 字节的分布推测）-> UTF-8 -> GB18030 -> Latin-1
+    /// 返回：编码、是否有 BOM、解码后的文本
+    pub fn decode(bytes: &[u8]) -> (Self, bool, String) {
+        if let Some(rest) = bytes.strip_prefix(UTF8_BOM)
+            && let Ok(text) = std::str::from_utf8(rest)
+        {
+            return (Self::Utf8, true, text.to_string());
+        }
+        if let Some(rest) = bytes.strip_prefix(UTF16LE_BOM)
+            && let Some(text) = Self::Utf16Le.decode_strictly(rest)
+        {
+            return (Self::Utf16Le, true, text);
+        }
+        if let Some(rest) = bytes.strip_prefix(UTF16BE_BOM)
+            && let Some(text) = Self::Utf16Be.decode_strictly(rest)
+        {
+            return (Self::Utf16Be, true, text);
+        }
+
+        // NUL 字节也是合法的 UTF-8，所以要先推测 UTF-16
+        if let Some(encoding) = Self::guess_utf16(bytes)
+            && let Some(text) = encoding.decode_strictly(bytes)
+        {
+            return (encoding, false, text);
+        }
+
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return (Self::Utf8, false, text.to_string());
+        }
+
+        if let Some(text) = Self::Gb18030.decode_strictly(bytes) {
+            return (Self::Gb18030, false, text);
+        }
+
+        // Latin-1 能解码任意字节，作为最后的兜底
+        let text = Self::Latin1.decode_strictly(bytes).unwrap_or_default();
+        (Self::Latin1, false, text)
+    }
+
+    /// 将文本编码为字节
+    /// 返回：编码后的字节，若文本中有该编码无法表示的字符则返回 None
+    pub fn encode(self, text: &str, bom: bool) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len());
+
+        match self {
+            Self::Utf8 => {
+                if bom {
+                    bytes.extend_from_slice(UTF8_BOM);
+                }
+                bytes.extend_from_slice(text.as_bytes());
+            }
+            Self::Utf16Le => {
+                if bom {
+                    bytes.extend_from_slice(UTF16LE_BOM);
+                }
+                text.encode_utf16()
+                    .for_each(|unit| bytes.extend_from_slice(&unit.to_le_bytes()));
+            }
+            Self::Utf16Be => {
+                if bom {
+                    bytes.extend_from_slice(UTF16BE_BOM);
+                }
+                text.encode_utf16()
+                    .for_each(|unit| bytes.extend_from_slice(&unit.to_be_bytes()));
+            }
+            Self::Gb18030 | Self::Latin1 => {
+                let (encoded, _, had_errors) = self.codec().encode(text);
+                if had_errors {
+                    return None;
+                }
+                bytes.extend_from_slice(&encoded);
+            }
+        }
+
+        Some(bytes)
+    }
+
+    /// 重新编码命令中的下一种编码
+    pub fn next(self) -> Self {
+        match self {
+            Self::Utf8 => Self::Utf16Le,
+            Self::Utf16Le => Self::Utf16Be,
+            Self::Utf16Be => Self::Gb18030,
+            Self::Gb18030 => Self::Latin1,
+            Self::Latin1 => Self::Utf8,
+        }
+    }
+
+    /// 编码习惯上是否带有 BOM，切换到该编码时使用
+    pub fn default_bom(self) -> bool {
+        matches!(self, Self::Utf16Le | Self::Utf16Be)
+    }
+
+    fn codec(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Self::Utf8 => encoding_rs::UTF_8,
+            Self::Utf16Le => UTF_16LE,
+            Self::Utf16Be => UTF_16BE,
+            Self::Gb18030 => GB18030,
+            Self::Latin1 => WINDOWS_1252,
+        }
+    }
+
+    /// 严格解码，遇到非法字节序列时返回 None，避免保存时写回被替换过的字符
+    fn decode_strictly(self, bytes: &[u8]) -> Option<String> {
+        self.codec()
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|text| text.into_owned())
+    }
+
+    /// 根据 NUL 字节的分布推测没有 BOM 的 UTF-16 文本
+    /// 以 ASCII 为主的 UTF-16LE 文本，奇数位置几乎都是 NUL；UTF-16BE 则是偶数位置
+    fn guess_utf16(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+            return None;
+        }
+
+        let units = bytes.len() / 2;
+        let even_nul = bytes.iter().step_by(2).filter(|&&byte| byte == 0).count();
+        let odd_nul = bytes
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|&&byte| byte == 0)
+            .count();
+
+        // 超过四成的码元在一侧为 NUL，而另一侧几乎没有 NUL
+        if odd_nul * 10 > units * 4 && even_nul * 10 < units {
+            Some(Self::Utf16Le)
+        } else if even_nul * 10 > units * 4 && odd_nul * 10 < units {
+            Some(Self::Utf16Be)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Utf8 => write!(formatter, "UTF-8"),
+            Self::Utf16Le => write!(formatter, "UTF-16LE"),
+            Self::Utf16Be => write!(formatter, "UTF-16BE"),
+            Self::Gb18030 => write!(formatter, "GB18030"),
+            Self::Latin1 => write!(formatter, "Latin-1"),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::file::{Encoding, LineEnding};
+
+/// 文件格式：文件在磁盘上的存储方式，加载时检测，保存时沿用
+#[derive(Default, Clone)]
+pub struct FileFormat {
+    // 字符编码
+    pub encoding: Encoding,
+    // 文件开头是否有字节顺序标记（BOM）
+    pub bom: bool,
+    // 换行风格，混用多种换行符时为出现最多的那种，新行也使用这种换行符
+    pub line_ending: LineEnding,
+    // 是否混用了多种换行符
+    pub mixed_line_endings: bool,
+    // 文件末尾是否有换行符
+    pub final_newline: bool,
+}
+
+impl Display for FileFormat {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        write!(formatter, "{}", self.encoding)?;
+        if self.bom {
+            write!(formatter, " BOM")?;
+        }
+        write!(formatter, " ")?;
+
+        if self.mixed_line_endings {
+            write!(formatter, "Mixed({})", self.line_ending)?;
+        } else {
+            write!(formatter, "{}", self.line_ending)?;
+        }
+
+        if !self.final_newline {
+            write!(formatter, " noeol")?;
+        }
+
+        Ok(())
+    }
+}
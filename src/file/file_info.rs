@@ -28,13 +28,21 @@ impl FileInfo {
 
         let file_type = match path.extension() {
             Some(ext) if ext.eq_ignore_ascii_case("rs") => FileType::Rust,
+            Some(ext)
+                if ["sh", "bash", "zsh"]
+                    .iter()
+                    .any(|sh| ext.eq_ignore_ascii_case(sh)) =>
+            {
+                FileType::Shell
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => FileType::Toml,
             _ => FileType::Text, // 此时无扩展名的情况已被处理（添加了txt），这里会匹配txt
         };
 
         FileInfo {
             name,
             file_type,
-            path: path,
+            path,
         }
     }
 
@@ -3,15 +3,113 @@ use std::fmt::{Display, Formatter, Result};
 #[derive(Default, Clone)]
 pub enum FileType {
     Rust,
+    Shell,
+    Toml,
     #[default]
     Text,
 }
 
+impl FileType {
+    /// 在其后换行时，新行增加一级缩进的字符
+    pub fn indent_triggers(&self) -> &'static [char] {
+        match self {
+            Self::Rust => &['{', '(', '['],
+            Self::Shell | Self::Toml => &['{', '['],
+            Self::Text => &[],
+        }
+    }
+
+    /// 在只有缩进的行中输入时，该行减少一级缩进的字符
+    pub fn dedent_triggers(&self) -> &'static [char] {
+        match self {
+            Self::Rust => &['}', ')', ']'],
+            Self::Shell | Self::Toml => &['}', ']'],
+            Self::Text => &[],
+        }
+    }
+
+    /// 输入左半边时自动补全右半边的成对字符；纯文本中撇号很常见，不补全单引号
+    pub fn auto_pairs(&self) -> &'static [(char, char)] {
+        match self {
+            Self::Rust | Self::Shell | Self::Toml => {
+                &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\'')]
+            }
+            Self::Text => &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"')],
+        }
+    }
+
+    /// 光标前的文本为 before 时，输入的单引号是否是生命周期的开头，此时不补全单引号
+    /// Rust 中紧跟 `&` 或 `<` 的单引号，以及未闭合的 `<` 中逗号或空白之后的单引号，是生命周期
+    pub fn starts_lifetime(&self, before: &str) -> bool {
+        if !matches!(self, Self::Rust) {
+            return false;
+        }
+
+        match before.chars().next_back() {
+            Some('&' | '<') => true,
+            Some(char) if char == ',' || char.is_whitespace() => {
+                before.matches('<').count() > before.matches('>').count()
+            }
+            _ => false,
+        }
+    }
+
+    /// 光标旁需要高亮配对的括号
+    pub fn brackets(&self) -> &'static [(char, char)] {
+        &[('(', ')'), ('[', ']'), ('{', '}')]
+    }
+
+    /// 行注释的起始符号，没有行注释的文件类型返回 None
+    pub fn line_comment(&self) -> Option<&'static str> {
+        match self {
+            Self::Rust => Some("//"),
+            Self::Shell | Self::Toml => Some("#"),
+            Self::Text => None,
+        }
+    }
+
+    /// 块注释的起止符号，没有块注释的文件类型返回 None
+    pub fn block_comment(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Rust => Some(("/*", "*/")),
+            Self::Shell | Self::Toml | Self::Text => None,
+        }
+    }
+}
+
 impl Display for FileType {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
         match self {
             Self::Rust => write!(formatter, "Rust"),
+            Self::Shell => write!(formatter, "Shell"),
+            Self::Toml => write!(formatter, "TOML"),
             Self::Text => write!(formatter, "Text"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rust_lifetimes_are_detected() {
+        for before in [
+            "fn f(x: &",
+            "struct S<",
+            "impl<'a, ",
+            "fn f<'a,",
+            "Foo<'a, T: ",
+        ] {
+            assert!(FileType::Rust.starts_lifetime(before), "{before}");
+        }
+    }
+
+    #[test]
+    fn char_literals_are_not_lifetimes() {
+        for before in ["let c = ", "f(a, ", "x == ", ""] {
+            assert!(!FileType::Rust.starts_lifetime(before), "{before}");
+        }
+        assert!(!FileType::Shell.starts_lifetime("echo <"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// 换行符风格
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl LineEnding {
+    /// 换行符对应的文本
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+            Self::Cr => "\r",
+        }
+    }
+
+    /// 转换命令中的下一种换行风格：LF -> CRLF -> CR -> LF
+    pub fn next(self) -> Self {
+        match self {
+            Self::Lf => Self::Crlf,
+            Self::Crlf => Self::Cr,
+            Self::Cr => Self::Lf,
+        }
+    }
+
+    /// 统计文本片段中各种换行符的出现次数，结果累加到 counts 中（顺序为 LF、CRLF、CR）
+    /// 文本片段不能把 CRLF 拆到两个片段中
+    pub fn count(text: &str, counts: &mut [usize; 3]) {
+        let bytes = text.as_bytes();
+        let mut idx = 0;
+
+        while idx < bytes.len() {
+            match bytes[idx] {
+                b'\r' if bytes.get(idx.saturating_add(1)) == Some(&b'\n') => {
+                    counts[1] += 1;
+                    idx += 1;
+                }
+                b'\r' => counts[2] += 1,
+                b'\n' => counts[0] += 1,
+                _ => {}
+            }
+            idx += 1;
+        }
+    }
+
+    /// 根据各种换行符的出现次数，返回出现最多的换行风格，以及是否混用了多种换行符
+    /// 没有任何换行符时，默认使用 LF
+    pub fn dominant(counts: &[usize; 3]) -> (Self, bool) {
+        let kinds = [Self::Lf, Self::Crlf, Self::Cr];
+
+        let mut dominant = Self::Lf;
+        let mut max_count = 0;
+        for (kind, &count) in kinds.into_iter().zip(counts) {
+            if count > max_count {
+                dominant = kind;
+                max_count = count;
+            }
+        }
+
+        let is_mixed = counts.iter().filter(|&&count| count > 0).count() > 1;
+
+        (dominant, is_mixed)
+    }
+
+    /// 将文本中的所有换行符统一为此换行符
+    pub fn normalize(self, text: &str) -> String {
+        Self::split_lines(text).join(self.as_str())
+    }
+
+    /// 按任意一种换行符（CRLF、LF、CR）拆分文本
+    pub fn split_lines(text: &str) -> Vec<&str> {
+        let bytes = text.as_bytes();
+        let mut lines = Vec::new();
+        let mut line_start = 0;
+        let mut idx = 0;
+
+        while idx < bytes.len() {
+            let break_len = match bytes[idx] {
+                b'\r' if bytes.get(idx.saturating_add(1)) == Some(&b'\n') => 2,
+                b'\r' | b'\n' => 1,
+                _ => 0,
+            };
+
+            if break_len > 0 {
+                lines.push(&text[line_start..idx]);
+                idx += break_len;
+                line_start = idx;
+            } else {
+                idx += 1;
+            }
+        }
+        lines.push(&text[line_start..]);
+
+        lines
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Lf => write!(formatter, "LF"),
+            Self::Crlf => write!(formatter, "CRLF"),
+            Self::Cr => write!(formatter, "CR"),
+        }
+    }
+}
@@ -1,4 +1,16 @@
+mod atomic_save;
+mod binary_kind;
+mod encoding;
+mod file_format;
 mod file_info;
 mod file_type;
+mod line_ending;
+mod swap_file;
+pub use atomic_save::AtomicSave;
+pub use binary_kind::BinaryKind;
+pub use encoding::Encoding;
+pub use file_format::FileFormat;
 pub use file_info::FileInfo;
 pub use file_type::FileType;
+pub use line_ending::LineEnding;
+pub use swap_file::{SwapFile, SwapState};
@@ -0,0 +1,234 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs, io,
+    path::{self, Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
+
+use crate::file::AtomicSave;
+
+// 交换文件首行的标记，用于识别 ye 写入的交换文件
+const SWAP_MAGIC: &str = "YE-SWAP 1";
+// 持续编辑、没有空闲时，两次写入交换文件的最长间隔
+const SWAP_MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 打开文件时交换文件的状态
+pub enum SwapState {
+    // 没有交换文件
+    Missing,
+    // 上次编辑时编辑器异常退出，遗留的交换文件中有未保存的修改
+    Orphaned,
+    // 另一个正在运行的 ye 进程（进程号）持有交换文件，即该进程中有未保存的修改
+    Busy(u32),
+}
+
+/// 交换文件：定期把未保存的文档写入交换文件，编辑器异常退出后，下次打开同一文件时可以从中恢复
+/// 交换文件优先放在被编辑文件的同目录下（`.文件名.ye.swp`），目录不可写时放在 $XDG_STATE_HOME/ye/swap 下；
+/// 交换文件首部记录了持有者的进程号，同时用作文件锁，提醒用户不要在两个 ye 进程中编辑同一个文件；
+/// 交换文件只在文档有未保存的修改时存在，保存或退出后即被删除，因此这把锁只在有未保存的修改期间生效，
+/// 打开文件后尚未编辑、或刚保存过的 ye 进程不会阻止另一个进程打开同一文件
+#[derive(Default)]
+pub struct SwapFile {
+    // 被编辑文件的路径
+    file_path: PathBuf,
+    // 交换文件的候选路径，按优先级排列
+    candidates: Vec<PathBuf>,
+    // 等待用户选择是否恢复的遗留交换文件
+    orphan: Option<PathBuf>,
+    // 本进程写入的交换文件
+    written: Option<PathBuf>,
+    // 上次写入交换文件时的文档版本
+    version: Option<usize>,
+    // 上次写入交换文件的时间
+    updated_at: Option<Instant>,
+    // 另一个进程持有交换文件时，本进程不写交换文件
+    is_disabled: bool,
+}
+
+impl SwapFile {
+    /// 为被编辑的文件查找交换文件
+    /// 返回：交换文件及其状态
+    pub fn open(file_path: &Path) -> (Self, SwapState) {
+        let mut swap_file = Self {
+            file_path: file_path.to_path_buf(),
+            candidates: Self::candidates(file_path),
+            ..Default::default()
+        };
+
+        let owner = swap_file
+            .candidates
+            .iter()
+            .find_map(|path| Self::read(path).ok().map(|(pid, _)| (path.clone(), pid)));
+
+        let state = match owner {
+            None => SwapState::Missing,
+            Some((_, pid)) if Self::is_running(pid) => {
+                swap_file.is_disabled = true;
+                SwapState::Busy(pid)
+            }
+            Some((path, _)) => {
+                swap_file.orphan = Some(path);
+                SwapState::Orphaned
+            }
+        };
+
+        (swap_file, state)
+    }
+
+    /// 文档版本与上次写入时不同，需要重新写入交换文件
+    pub fn is_outdated(&self, version: usize) -> bool {
+        !self.is_disabled && self.version != Some(version)
+    }
+
+    /// 距上次写入交换文件已超过最长间隔
+    pub fn is_overdue(&self) -> bool {
+        self.updated_at
+            .is_none_or(|updated_at| updated_at.elapsed() >= SWAP_MAX_INTERVAL)
+    }
+
+    /// 把文档内容写入交换文件，同目录不可写时改写到状态目录
+    /// 写入失败时同样记下版本，文档再次修改前不会重试，避免反复报错
+    pub fn write(&mut self, text: &str, version: usize) -> io::Result<()> {
+        if self.is_disabled {
+            return Ok(());
+        }
+
+        self.version = Some(version);
+        self.updated_at = Some(Instant::now());
+
+        let contents = format!("{SWAP_MAGIC}\n{}\n{text}", process::id());
+        let mut result = Err(io::Error::other("没有可用的交换文件路径"));
+
+        // 未决定是否恢复的遗留交换文件不能被覆盖
+        for path in self
+            .candidates
+            .iter()
+            .filter(|path| self.orphan.as_ref() != Some(*path))
+        {
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                let _ = fs::create_dir_all(parent);
+            }
+
+            result = AtomicSave::write(path, contents.as_bytes(), false);
+            if result.is_ok() {
+                self.claim(path.clone());
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// 读取遗留交换文件中的文本，之后该交换文件归本进程所有
+    pub fn recover(&mut self) -> io::Result<String> {
+        let path = self
+            .orphan
+            .clone()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        let (_, text) = Self::read(&path)?;
+
+        self.orphan = None;
+        self.claim(path);
+        // 恢复的内容尚未写入本进程的交换文件
+        self.version = None;
+        self.updated_at = None;
+
+        Ok(text)
+    }
+
+    /// 删除遗留的交换文件
+    pub fn discard(&mut self) {
+        if let Some(path) = self.orphan.take() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// 删除本进程写入的交换文件，文档保存后或退出编辑器时调用
+    pub fn remove(&mut self) {
+        if let Some(path) = self.written.take() {
+            let _ = fs::remove_file(path);
+        }
+        self.version = None;
+    }
+
+    /// 记下本进程写入的交换文件，换了位置时删除旧的交换文件
+    /// 交换文件首次创建时沿用被编辑文件的权限，避免其他用户读到文件内容
+    fn claim(&mut self, path: PathBuf) {
+        match self.written.replace(path.clone()) {
+            Some(old) if old == path => return,
+            Some(old) => {
+                let _ = fs::remove_file(old);
+            }
+            None => {}
+        }
+
+        if let Ok(metadata) = fs::metadata(&self.file_path) {
+            let _ = fs::set_permissions(&path, metadata.permissions());
+        }
+    }
+
+    /// 读取交换文件，返回持有者的进程号和文本
+    fn read(path: &Path) -> io::Result<(u32, String)> {
+        let contents = fs::read_to_string(path)?;
+        let mut parts = contents.splitn(3, '\n');
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(SWAP_MAGIC), Some(pid), Some(text)) => pid
+                .parse()
+                .map(|pid| (pid, text.to_string()))
+                .map_err(io::Error::other),
+            _ => Err(io::Error::other("不是 ye 的交换文件")),
+        }
+    }
+
+    /// 交换文件的候选路径：被编辑文件的同目录，以及状态目录
+    fn candidates(file_path: &Path) -> Vec<PathBuf> {
+        let mut file_name = OsString::from(".");
+        file_name.push(file_path.file_name().unwrap_or_default());
+        file_name.push(".ye.swp");
+
+        let mut candidates = vec![file_path.with_file_name(file_name)];
+
+        // 状态目录中的交换文件以被编辑文件的绝对路径命名，路径分隔符替换为 %
+        let state_home = env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")));
+        if let Some(state_home) = state_home
+            && let Ok(absolute) = path::absolute(file_path)
+        {
+            let name = absolute
+                .to_string_lossy()
+                .replace(path::MAIN_SEPARATOR, "%");
+            candidates.push(state_home.join("ye").join("swap").join(name + ".swp"));
+        }
+
+        candidates
+    }
+
+    /// 进程是否仍在运行，本进程的进程号只可能来自被复用的旧交换文件
+    #[cfg(unix)]
+    fn is_running(pid: u32) -> bool {
+        if pid == process::id() {
+            return false;
+        }
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return false;
+        };
+
+        // 信号 0 只检查进程是否存在，并不真正发送信号；没有权限发送信号说明进程存在
+        // SAFETY: kill 不涉及任何内存访问
+        let result = unsafe { libc::kill(pid, 0) };
+
+        result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+
+    /// 无法判断进程是否存在时，视为已退出，交由用户选择是否恢复
+    #[cfg(not(unix))]
+    fn is_running(_: u32) -> bool {
+        false
+    }
+}
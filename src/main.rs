@@ -1,5 +1,6 @@
 use std::env;
 
+mod config;
 mod editor;
 mod file;
 mod prelude;
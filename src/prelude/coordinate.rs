@@ -4,32 +4,18 @@ use crate::prelude::idx::{CellIdx, ColIdx, LineIdx, RowIdx};
 
 /// 文档坐标，一般用来：
 /// 标识光标在文档中的哪个位置
-#[derive(Clone)]
+/// 文档坐标之间可以比较先后：先比较行索引，再比较图元索引
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DocumentCoordinate {
     pub line_idx: LineIdx,
     pub cell_idx: CellIdx,
 }
 
-impl Default for DocumentCoordinate {
-    fn default() -> Self {
-        Self {
-            line_idx: 0,
-            cell_idx: 0,
-        }
-    }
-}
-
 /// 终端坐标，一般用来：
 /// 标识滚动偏移量
 /// 标识光标在终端中的哪个位置
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct TerminalCoordinate {
     pub row: RowIdx,
     pub col: ColIdx,
 }
-
-impl Default for TerminalCoordinate {
-    fn default() -> Self {
-        Self { row: 0, col: 0 }
-    }
-}
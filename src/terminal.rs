@@ -1,10 +1,11 @@
 use crossterm::{
     Command,
     cursor::{Hide, MoveTo, Show},
+    event::{DisableBracketedPaste, EnableBracketedPaste},
     queue,
     style::{
         Attribute::{Reset, Reverse},
-        Print,
+        Color, Colored, Print,
     },
     terminal::{
         self, Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
@@ -16,6 +17,7 @@ use std::io::{Write, stdout};
 use crate::prelude::{RowIdx, Size, TerminalCoordinate};
 
 /// 我们使用crossterm库来实现终端的控制功能
+#[derive(Default)]
 pub struct Terminal {
     title: String,
 }
@@ -40,14 +42,16 @@ impl Terminal {
         enable_raw_mode().expect("Failed to enable raw mode"); // 1. 进入原始模式（禁用终端默认行为）
         Self::enter_alternate_screen(); // 2. 进入备用屏幕（独立缓冲区，不干扰原终端）
         Self::disable_line_wrap(); // 3. 禁用自动换行（编辑器自己控制换行）
-        Self::clear_screen(); // 4. 清空备用屏幕（初始化显示）
-        Self::execute(); // 5. 执行所有命令（刷新缓冲区）
+        Self::queue_command(EnableBracketedPaste); // 4. 开启括号粘贴（粘贴的文本整体到达，而不是逐个按键）
+        Self::clear_screen(); // 5. 清空备用屏幕（初始化显示）
+        Self::execute(); // 6. 执行所有命令（刷新缓冲区）
     }
 
     /// 终止终端（恢复默认状态）
     pub fn terminate() {
         // 1. 在备用屏幕内恢复终端属性（避免影响原始终端）
         Self::enable_line_wrap(); // 恢复自动换行
+        Self::queue_command(DisableBracketedPaste); // 关闭括号粘贴
         Self::show_caret(); // 恢复光标显示
         Self::clear_screen(); // 清空备用屏幕（可选，避免残留）
         Self::execute(); // 执行备用屏幕内的清理命令
@@ -118,6 +122,47 @@ impl Terminal {
         Self::print_row(row, &format!("{Reverse}{line_text:width$.width$}{Reset}"))
     }
 
+    /// 在终端上打印一行错误消息，以红底白字显示
+    pub fn print_error_row(row: RowIdx, line_text: &str) {
+        let width = Self::size().width;
+        let background = Colored::BackgroundColor(Color::DarkRed);
+        let foreground = Colored::ForegroundColor(Color::White);
+        Self::print_row(
+            row,
+            &format!("{background}{foreground}{line_text:width$.width$}{Reset}"),
+        )
+    }
+
+    /// 通过 OSC 52 控制序列把文本复制到终端所在主机的剪贴板，经过 SSH 时同样有效
+    /// tmux 默认（set-clipboard external）会丢弃程序发出的 OSC 52，需要开启 set-clipboard on
+    pub fn copy_to_clipboard(text: &str) {
+        Self::print(&format!("\x1b]52;c;{}\x07", Self::base64(text.as_bytes())));
+        Self::execute();
+    }
+
+    /// Base64 编码，OSC 52 要求剪贴板内容以 Base64 传输
+    fn base64(bytes: &[u8]) -> String {
+        const TABLE: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut encoded = String::with_capacity(bytes.len().div_ceil(3).saturating_mul(4));
+        for chunk in bytes.chunks(3) {
+            let group = chunk.iter().enumerate().fold(0u32, |group, (idx, &byte)| {
+                group | u32::from(byte) << (16 - 8 * idx)
+            });
+
+            for idx in 0..4 {
+                if idx <= chunk.len() {
+                    encoded.push(char::from(TABLE[(group >> (18 - 6 * idx)) as usize & 63]));
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+
+        encoded
+    }
+
     /// 在终端上打印字符串
     fn print(string: &str) {
         Self::queue_command(Print(string));
@@ -133,11 +178,3 @@ impl Terminal {
         let _ = queue!(stdout(), command);
     }
 }
-
-impl Default for Terminal {
-    fn default() -> Self {
-        Self {
-            title: String::new(),
-        }
-    }
-}